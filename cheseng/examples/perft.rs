@@ -0,0 +1,30 @@
+//! Times perft to measure move generation speed in nodes per second.
+//!
+//! Run with `cargo run --release -p cheseng --example perft [depth] [fen]`, which defaults to
+//! depth 5 from the start position.
+
+use cheseng::perft::perft;
+use cheseng::Board;
+use std::time::Instant;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let depth = args
+        .next()
+        .map_or(5, |depth| depth.parse().expect("invalid depth"));
+    let board = match args.next() {
+        Some(fen) => Board::from_fen(&fen).expect("invalid fen"),
+        None => Board::default(),
+    };
+
+    let start_time = Instant::now();
+    let num_nodes = perft(&board, depth);
+    let elapsed = start_time.elapsed().as_secs_f64();
+
+    println!(
+        "Nodes: {} in {:.3}s ({:.0} nodes/s)",
+        num_nodes,
+        elapsed,
+        num_nodes as f64 / elapsed
+    );
+}
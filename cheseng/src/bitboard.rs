@@ -0,0 +1,427 @@
+use crate::Color;
+use std::ops::Range;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use std::sync::LazyLock;
+
+/// A set of squares where each bit is a grid index (a8 is bit 0, h1 is bit 63).
+/// Iterating over a bitboard gives the grid index of each square in the set.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Self = Self(0);
+    pub const FULL: Self = Self(!0);
+
+    pub const fn from_index(index: u8) -> Self {
+        Self(1 << index)
+    }
+
+    pub const fn contains(self, index: u8) -> bool {
+        self.0 & (1 << index) != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Gets the lowest grid index in the set
+    pub const fn lsb(self) -> Option<u8> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.0.trailing_zeros() as u8)
+        }
+    }
+
+    /// Gets the highest grid index in the set
+    pub const fn msb(self) -> Option<u8> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(63 - self.0.leading_zeros() as u8)
+        }
+    }
+
+    pub const fn has_more_than_one(self) -> bool {
+        self.0 & self.0.wrapping_sub(1) != 0
+    }
+}
+
+impl Iterator for Bitboard {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let index = self.lsb()?;
+        // remove lowest bit
+        self.0 &= self.0 - 1;
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.count() as usize;
+        (count, Some(count))
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op_trait: ident, $op_fn: ident, $assign_trait: ident, $assign_fn: ident) => {
+        impl $op_trait for Bitboard {
+            type Output = Self;
+
+            fn $op_fn(self, rhs: Self) -> Self {
+                Self($op_trait::$op_fn(self.0, rhs.0))
+            }
+        }
+
+        impl $assign_trait for Bitboard {
+            fn $assign_fn(&mut self, rhs: Self) {
+                $assign_trait::$assign_fn(&mut self.0, rhs.0)
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for Bitboard {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+pub(crate) fn knight_attacks(index: u8) -> Bitboard {
+    Bitboard(KNIGHT_ATTACKS[index as usize])
+}
+
+pub(crate) fn king_attacks(index: u8) -> Bitboard {
+    Bitboard(KING_ATTACKS[index as usize])
+}
+
+/// Gets the squares a pawn of the colour attacks from the square at index
+pub(crate) fn pawn_attacks(index: u8, color: Color) -> Bitboard {
+    Bitboard(PAWN_ATTACKS[color.as_index()][index as usize])
+}
+
+pub(crate) fn rook_attacks(index: u8, occupancy: Bitboard) -> Bitboard {
+    ROOK_ATTACKS.get(index, occupancy)
+}
+
+pub(crate) fn bishop_attacks(index: u8, occupancy: Bitboard) -> Bitboard {
+    BISHOP_ATTACKS.get(index, occupancy)
+}
+
+/// Gets the squares between two squares on the same line not including the squares themselves.
+/// Empty if they aren't on the same line.
+pub(crate) fn between(index_a: u8, index_b: u8) -> Bitboard {
+    Bitboard(BETWEEN[index_a as usize][index_b as usize])
+}
+
+/// Gets the whole line going through two squares from edge to edge.
+/// Empty if they aren't on the same line.
+pub(crate) fn line(index_a: u8, index_b: u8) -> Bitboard {
+    Bitboard(LINE[index_a as usize][index_b as usize])
+}
+
+/// Lookup table of the attacks of a sliding piece for every square and arrangement of pieces that
+/// could block it
+struct SliderAttacks {
+    entries: [MagicEntry; 64],
+    table: Vec<Bitboard>,
+}
+
+/// Where the attacks for a square are in the table. The blockers on the mask are turned into an
+/// index either by multiplying by the magic number and keeping the top bits or, with the `pext`
+/// feature on a CPU with BMI2, by extracting the masked bits.
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(
+    all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2"),
+    allow(dead_code)
+)]
+struct MagicEntry {
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl MagicEntry {
+    fn index(&self, occupancy: Bitboard) -> usize {
+        #[cfg(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2"))]
+        {
+            // SAFETY: only compiled when the target has BMI2
+            let blockers = unsafe { std::arch::x86_64::_pext_u64(occupancy.0, self.mask) };
+            self.offset + blockers as usize
+        }
+
+        #[cfg(not(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2")))]
+        {
+            let blockers = occupancy.0 & self.mask;
+            self.offset + (blockers.wrapping_mul(self.magic) >> self.shift) as usize
+        }
+    }
+}
+
+impl SliderAttacks {
+    /// Fills the table by walking the rays for every subset of blockers on every square
+    fn new(magics: &[u64; 64], dir_range: Range<usize>) -> Self {
+        let mut entries = [MagicEntry::default(); 64];
+        let mut table = Vec::new();
+        for (index, entry) in entries.iter_mut().enumerate() {
+            // pieces on the edge of the grid can't block anything further
+            let mask = dir_range.clone().fold(0, |mask, dir_index| {
+                let ray = Bitboard(RAYS[dir_index][index]);
+                let edge = if DIRECTION_OFFSETS[dir_index] > 0 {
+                    ray.msb()
+                } else {
+                    ray.lsb()
+                };
+                mask | edge.map_or(0, |edge_index| ray.0 ^ 1 << edge_index)
+            });
+
+            let num_bits = mask.count_ones();
+            *entry = MagicEntry {
+                mask,
+                magic: magics[index],
+                shift: 64 - num_bits,
+                offset: table.len(),
+            };
+            table.resize(table.len() + (1 << num_bits), Bitboard::EMPTY);
+
+            // go through every subset of the mask (Carry-Rippler trick)
+            let mut blockers = 0u64;
+            loop {
+                let attacks = dir_range
+                    .clone()
+                    .fold(Bitboard::EMPTY, |attacks, dir_index| {
+                        attacks | ray_attacks(dir_index, index as u8, Bitboard(blockers))
+                    });
+
+                // a magic can send different blockers to the same slot only if the attacks match
+                let slot = &mut table[entry.index(Bitboard(blockers))];
+                debug_assert!(
+                    slot.is_empty() || *slot == attacks,
+                    "magic for square {} maps blockers {:#x} onto different attacks",
+                    index,
+                    blockers
+                );
+                *slot = attacks;
+
+                blockers = blockers.wrapping_sub(mask) & mask;
+                if blockers == 0 {
+                    break;
+                }
+            }
+        }
+
+        Self { entries, table }
+    }
+
+    fn get(&self, index: u8, occupancy: Bitboard) -> Bitboard {
+        self.table[self.entries[index as usize].index(occupancy)]
+    }
+}
+
+static ROOK_ATTACKS: LazyLock<SliderAttacks> =
+    LazyLock::new(|| SliderAttacks::new(&ROOK_MAGICS, 0..4));
+static BISHOP_ATTACKS: LazyLock<SliderAttacks> =
+    LazyLock::new(|| SliderAttacks::new(&BISHOP_MAGICS, 4..8));
+
+/// Squares attacked in a direction stopping at (and including) the first occupied square
+fn ray_attacks(dir_index: usize, index: u8, occupancy: Bitboard) -> Bitboard {
+    let ray = RAYS[dir_index][index as usize];
+    let blockers = Bitboard(ray) & occupancy;
+
+    // nearest blocker is the lowest index if the direction goes up in index
+    let nearest_blocker = if DIRECTION_OFFSETS[dir_index] > 0 {
+        blockers.lsb()
+    } else {
+        blockers.msb()
+    };
+
+    match nearest_blocker {
+        Some(blocker_index) => Bitboard(ray ^ RAYS[dir_index][blocker_index as usize]),
+        None => Bitboard(ray),
+    }
+}
+
+// Array containing the index offset needed to move once in a direction as an index in the order:
+// (N, S, W, E, NW, NE, SW, SE) assuming origin is top left
+const DIRECTION_OFFSETS: [i8; 8] = [-8, 8, -1, 1, -9, -7, 7, 9];
+
+// Same directions as DIRECTION_OFFSETS as (file, rank) steps
+const DIRECTION_STEPS: [(i8, i8); 8] = [
+    (0, -1),
+    (0, 1),
+    (-1, 0),
+    (1, 0),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+const KNIGHT_STEPS: [(i8, i8); 8] = [
+    (-1, -2),
+    (1, -2),
+    (-2, -1),
+    (2, -1),
+    (-2, 1),
+    (2, 1),
+    (-1, 2),
+    (1, 2),
+];
+
+const KNIGHT_ATTACKS: [u64; 64] = calc_step_attacks(&KNIGHT_STEPS);
+const KING_ATTACKS: [u64; 64] = calc_step_attacks(&DIRECTION_STEPS);
+const PAWN_ATTACKS: [[u64; 64]; 2] = [
+    calc_step_attacks(&[(-1, -1), (1, -1)]),
+    calc_step_attacks(&[(-1, 1), (1, 1)]),
+];
+
+// Squares in each direction (N, S, W, E, NW, NE, SW, SE) from each square to the edge of the grid
+const RAYS: [[u64; 64]; 8] = calc_rays();
+static BETWEEN: [[u64; 64]; 64] = calc_between();
+static LINE: [[u64; 64]; 64] = calc_lines();
+
+/// Gets the grid index after stepping from the index or none if it goes outside the grid
+const fn step(index: usize, (file_step, rank_step): (i8, i8)) -> Option<usize> {
+    let file = (index % 8) as i8 + file_step;
+    let rank = (index / 8) as i8 + rank_step;
+    if file < 0 || file > 7 || rank < 0 || rank > 7 {
+        None
+    } else {
+        Some((rank * 8 + file) as usize)
+    }
+}
+
+const fn calc_step_attacks(steps: &[(i8, i8)]) -> [u64; 64] {
+    let mut attacks = [0; 64];
+    let mut index = 0;
+    while index < 64 {
+        let mut i = 0;
+        while i < steps.len() {
+            if let Some(end_index) = step(index, steps[i]) {
+                attacks[index] |= 1 << end_index;
+            }
+            i += 1;
+        }
+        index += 1;
+    }
+
+    attacks
+}
+
+const fn calc_rays() -> [[u64; 64]; 8] {
+    let mut rays = [[0; 64]; 8];
+    let mut dir_index = 0;
+    while dir_index < 8 {
+        let mut index = 0;
+        while index < 64 {
+            // keep stepping until reach edge of board
+            let mut current_index = index;
+            while let Some(end_index) = step(current_index, DIRECTION_STEPS[dir_index]) {
+                rays[dir_index][index] |= 1 << end_index;
+                current_index = end_index;
+            }
+            index += 1;
+        }
+        dir_index += 1;
+    }
+
+    rays
+}
+
+const fn calc_between() -> [[u64; 64]; 64] {
+    let mut between = [[0; 64]; 64];
+    let mut index = 0;
+    while index < 64 {
+        let mut dir_index = 0;
+        while dir_index < 8 {
+            // every square passed through on the way to another square is between them
+            let mut passed = 0;
+            let mut current_index = index;
+            while let Some(end_index) = step(current_index, DIRECTION_STEPS[dir_index]) {
+                between[index][end_index] = passed;
+                passed |= 1 << end_index;
+                current_index = end_index;
+            }
+            dir_index += 1;
+        }
+        index += 1;
+    }
+
+    between
+}
+
+const fn calc_lines() -> [[u64; 64]; 64] {
+    let mut lines = [[0; 64]; 64];
+    let mut index = 0;
+    while index < 64 {
+        let mut dir_index = 0;
+        while dir_index < 8 {
+            // directions come in opposite pairs so combine both halves of the line
+            let opposite_dir_index = dir_index ^ 1;
+            let line = RAYS[dir_index][index] | RAYS[opposite_dir_index][index] | 1 << index;
+            let mut ray = RAYS[dir_index][index];
+            while ray != 0 {
+                let end_index = ray.trailing_zeros() as usize;
+                lines[index][end_index] = line;
+                ray &= ray - 1;
+            }
+            dir_index += 1;
+        }
+        index += 1;
+    }
+
+    lines
+}
+
+// Magic numbers for the grid layout (a8 first) found by trying random sparse numbers until every
+// subset of blockers mapped to an index without a conflicting set of attacks
+#[rustfmt::skip]
+const ROOK_MAGICS: [u64; 64] = [
+    0x0A80_0040_0080_1220, 0x10C0_1000_4000_2000, 0x0100_1020_0041_0009, 0x0B00_2100_0C10_0008,
+    0x4080_0800_8004_0002, 0x0200_0190_0408_0200, 0x0400_080A_1011_2684, 0x2080_0A4D_0006_2080,
+    0x2091_8000_2080_4000, 0x0044_4010_0020_0040, 0x1001_0020_0040_1108, 0x1001_8008_0110_0081,
+    0x0001_0005_0008_0010, 0x1000_8080_0200_0400, 0x0404_0004_8210_0108, 0x0003_0001_8261_0002,
+    0x0440_8480_02C0_0420, 0x2010_8900_4001_0021, 0x8800_1100_2004_4300, 0x0208_0101_0020_1000,
+    0x1222_0200_0410_2008, 0x0000_8080_0200_0400, 0x2004_0400_094A_9008, 0x0000_4200_0080_4401,
+    0x0040_0028_8000_4680, 0x0000_2002_4010_0040, 0x0020_0081_8020_1001, 0x0108_0080_800C_1000,
+    0x0104_0400_8080_0800, 0x4800_0200_8004_0080, 0x0002_0002_0084_0108, 0x00A1_0001_0000_6082,
+    0x8004_4000_8880_0260, 0x0100_8040_0080_2008, 0x0010_0080_1080_2002, 0x000C_8010_0080_0800,
+    0x0C51_8004_0280_0800, 0x0002_8002_0080_0400, 0x0000_8208_0400_0110, 0x4003_8080_4200_0401,
+    0x0020_8020_C001_8000, 0x4400_4020_1000_4009, 0x2210_0400_A800_E000, 0x0E02_0021_400A_0013,
+    0x10A0_0801_0011_0005, 0x0004_0100_0200_4040, 0x0024_0801_0204_0010, 0x4154_0891_0842_0014,
+    0x0182_4000_8000_2380, 0x0000_4001_1080_2100, 0x0000_1000_8020_0480, 0x100A_0008_2040_1200,
+    0x8081_0040_2080_1002, 0x0002_0004_0810_0200, 0x0322_3A10_0801_0C00, 0x0000_0083_1C01_4200,
+    0x4200_2080_0900_1041, 0xC001_0040_0088_1021, 0x1008_2001_0010_0841, 0x0000_0822_4092_0032,
+    0x4002_0008_0420_1102, 0xB821_0008_0400_0201, 0x4080_C208_1021_00A4, 0x0202_0900_418C_0CA2,
+];
+
+#[rustfmt::skip]
+const BISHOP_MAGICS: [u64; 64] = [
+    0x002A_8404_0184_0308, 0x0002_0484_0400_4000, 0x1088_5081_0602_0000, 0x0604_0404_8400_0420,
+    0x1002_0210_0438_0001, 0x8041_0482_4000_0A30, 0x4084_0441_0410_3110, 0x0081_0040_4420_0840,
+    0x0442_4110_A101_0901, 0x0042_8208_4104_0080, 0x1001_0802_0400_2C09, 0x0001_4804_A104_1815,
+    0x0004_8202_1004_1001, 0x0001_8104_0340_0040, 0x0802_4041_0420_A084, 0x0410_1202_0101_0900,
+    0x0240_0485_0428_0200, 0x9402_0004_9004_0325, 0x2003_0010_1C09_8030, 0x0004_0028_4040_0800,
+    0x0002_0104_0211_0140, 0x0000_4032_0100_A060, 0x0042_0000_6104_6000, 0x0188_3000_8468_4808,
+    0x0010_1011_0802_1022, 0x8724_0480_2109_0C00, 0x502C_0202_C408_0010, 0x0008_0822_4802_0020,
+    0x0001_0200_8400_8400, 0x0891_0040_0208_2001, 0x000A_0210_0424_8200, 0x0011_0200_012A_8402,
+    0x2042_2084_3020_3904, 0x0C08_6208_1611_1880, 0x0002_0450_0441_0100, 0x0800_1201_8018_0080,
+    0x0140_0100_12C1_0040, 0x0050_1008_4040_2400, 0x0808_0200_8000_4801, 0x0004_8203_4102_0100,
+    0x001A_0124_2010_C080, 0x2018_6202_1001_2008, 0x8021_0400_220A_0400, 0x0020_0142_0082_0801,
+    0x0100_0881_0041_C400, 0x0020_0408_8020_5A01, 0x0010_8101_1102_E420, 0x0081_0604_810B_0208,
+    0x0000_6210_0421_0094, 0x0200_2108_0210_5811, 0x8008_0080_5808_0200, 0x5800_4000_8404_0010,
+    0x0000_0090_0202_2880, 0x9000_8830_0102_1010, 0x804A_8284_0404_0006, 0x2010_1218_0100_2800,
+    0x4012_0200_8401_0846, 0x8002_4212_0202_0200, 0x6104_0400_2084_1000, 0x0000_0002_0504_8804,
+    0x0808_0080_4110_2480, 0x2305_9040_0204_0440, 0x0810_4042_8202_0204, 0x0588_2001_0200_2100,
+];
@@ -1,12 +1,36 @@
-use crate::{Color, Error, Move, MoveFlag, Piece, Position, Side};
+use crate::bitboard::{self, Bitboard};
+use crate::{zobrist, Color, DrawReason, GameStatus, Move, MoveFlag, Piece, Side};
 
+#[derive(Clone)]
 pub struct Board {
     pub turn: Color,
-    pub grid: [Option<Piece>; 64],
+    grid: [Option<Piece>; 64],
+    /// Squares of each type of piece of both colours indexed by [Piece::kind_index]
+    pub(crate) pieces: [Bitboard; 6],
+    /// Squares of the pieces of each colour indexed by [Color::as_index]
+    pub(crate) colors: [Bitboard; 2],
     pub en_passant_square: Option<u8>,
     /// Array with the castle rights for a colour with white first, black second as 2 legth array
     /// of bools with queenside first kingside second
     pub castle_rights: [[bool; 2]; 2],
+    /// Number of halfmoves since the last capture or pawn move
+    pub halfmove_clock: u32,
+    /// Number of the current move starting at 1 and incremented after black moves
+    pub fullmove_number: u32,
+    /// Information needed to unmake each move made on the board in order
+    undo_stack: Vec<UndoInfo>,
+    zobrist_key: u64,
+}
+
+/// The state of the board before a move that can't be worked out from the move itself
+#[derive(Clone)]
+struct UndoInfo {
+    made_move: Move,
+    captured_piece: Option<Piece>,
+    en_passant_square: Option<u8>,
+    castle_rights: [[bool; 2]; 2],
+    halfmove_clock: u32,
+    zobrist_key: u64,
 }
 
 impl Board {
@@ -15,78 +39,99 @@ impl Board {
     pub fn empty() -> Self {
         Self {
             grid: [None; 64],
+            pieces: [Bitboard::EMPTY; 6],
+            colors: [Bitboard::EMPTY; 2],
             turn: Color::White,
             en_passant_square: None,
             castle_rights: [[false, false]; 2],
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            undo_stack: Vec::new(),
+            zobrist_key: zobrist::turn_key(),
         }
     }
 
-    /// Creates a new board from Forsyth-Edwards Notation.
-    // TODO: parse the remaining information
-    pub fn from_fen(fen: &str) -> Result<Self, Error> {
-        let mut board = Self::empty();
-        let mut sections = fen.split_whitespace();
-
-        let mut pos = Position::new(0, 0);
-        for char in sections.next().ok_or(Error::InvalidFEN)?.chars() {
-            let piece = match char {
-                'P' => Piece::Pawn(Color::White),
-                'N' => Piece::Knight(Color::White),
-                'B' => Piece::Bishop(Color::White),
-                'R' => Piece::Rook(Color::White),
-                'Q' => Piece::Queen(Color::White),
-                'K' => Piece::King(Color::White),
-
-                'p' => Piece::Pawn(Color::Black),
-                'n' => Piece::Knight(Color::Black),
-                'b' => Piece::Bishop(Color::Black),
-                'r' => Piece::Rook(Color::Black),
-                'q' => Piece::Queen(Color::Black),
-                'k' => Piece::King(Color::Black),
-
-                '/' => {
-                    pos.rank += 1;
-                    pos.file = 0;
-                    continue;
-                }
+    /// Gets the Zobrist key of the position which is compatible with Polyglot opening books.
+    /// It is updated when moves are made so if the fields are changed directly
+    /// [Board::update_zobrist_key] should be called.
+    pub fn zobrist_key(&self) -> u64 {
+        self.zobrist_key
+    }
 
-                '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
-                    pos.file += char.to_digit(10).unwrap() as u8;
-                    continue;
-                }
+    /// Recalculates the Zobrist key of the position from scratch
+    pub fn update_zobrist_key(&mut self) {
+        self.zobrist_key = self.calc_zobrist_key();
+    }
 
-                _ => Err(Error::InvalidFENStr(char.into()))?,
-            };
+    fn calc_zobrist_key(&self) -> u64 {
+        let mut key = zobrist::castle_rights_key(&self.castle_rights);
+        for (index, piece) in self.grid.iter().enumerate() {
+            if let Some(piece) = piece {
+                key ^= zobrist::piece_key(*piece, index);
+            }
+        }
 
-            board.grid[pos.as_index()? as usize] = Some(piece);
-            pos.file += 1;
+        if self.turn == Color::White {
+            key ^= zobrist::turn_key();
         }
 
-        board.turn = match sections.next().ok_or(Error::InvalidFEN)? {
-            "w" => Color::White,
-            "b" => Color::Black,
-            turn_str => Err(Error::InvalidFENStr(turn_str.into()))?,
-        };
+        key ^ self.en_passant_key()
+    }
 
-        let castle_rights_str = sections.next().ok_or(Error::InvalidFEN)?;
-        if castle_rights_str != "-" {
-            for char in castle_rights_str.chars() {
-                match char {
-                    'Q' => board.castle_rights[0][0] = true,
-                    'K' => board.castle_rights[0][1] = true,
-                    'k' => board.castle_rights[1][0] = true,
-                    'q' => board.castle_rights[1][1] = true,
-                    char => Err(Error::InvalidFENStr(char.into()))?,
-                }
+    /// Gets the key for the en passant square which is only included if it can be captured
+    fn en_passant_key(&self) -> u64 {
+        match self.en_passant_square {
+            Some(index) if self.can_capture_en_passant() => {
+                zobrist::en_passant_key(index as usize % 8)
             }
+            _ => 0,
         }
+    }
 
-        let en_passant_char = sections.next().ok_or(Error::InvalidFEN)?;
-        if en_passant_char != "-" {
-            board.en_passant_square = Some(en_passant_char.parse::<Position>()?.as_index()?);
-        }
+    /// Gets the piece on each square of the grid starting from a8
+    pub fn grid(&self) -> &[Option<Piece>; 64] {
+        &self.grid
+    }
+
+    pub fn piece_at(&self, index: u8) -> Option<Piece> {
+        self.grid[index as usize]
+    }
+
+    /// Puts a piece on (or removes a piece from) the square at index keeping the Zobrist key up to
+    /// date. Like changing the other fields directly this doesn't check the position is valid.
+    pub fn set_piece(&mut self, index: u8, piece: Option<Piece>) {
+        self.set_square(index as usize, piece);
+    }
+
+    /// Gets the squares of all the pieces on the board
+    pub fn occupancy(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
 
-        Ok(board)
+    /// Gets the squares of all the pieces of one colour
+    pub fn color_pieces(&self, color: Color) -> Bitboard {
+        self.colors[color.as_index()]
+    }
+
+    /// Gets the squares with the exact piece on them
+    pub fn pieces(&self, piece: Piece) -> Bitboard {
+        self.pieces[piece.kind_index()] & self.colors[piece.get_color().as_index()]
+    }
+
+    /// Sets a square on the grid and updates the bitboards and Zobrist key
+    fn set_square(&mut self, index: usize, piece: Option<Piece>) {
+        let square = Bitboard::from_index(index as u8);
+        if let Some(old_piece) = self.grid[index] {
+            self.zobrist_key ^= zobrist::piece_key(old_piece, index);
+            self.pieces[old_piece.kind_index()] ^= square;
+            self.colors[old_piece.get_color().as_index()] ^= square;
+        }
+        if let Some(piece) = piece {
+            self.zobrist_key ^= zobrist::piece_key(piece, index);
+            self.pieces[piece.kind_index()] ^= square;
+            self.colors[piece.get_color().as_index()] ^= square;
+        }
+        self.grid[index] = piece;
     }
 
     /// Moves a using specified move's start and end square index.
@@ -96,8 +141,28 @@ impl Board {
         let piece = self.grid[start_i];
         let end_piece = self.grid[end_i];
 
-        self.grid[start_i] = None;
-        self.grid[end_i] = piece;
+        self.undo_stack.push(UndoInfo {
+            made_move: raw_move,
+            captured_piece: end_piece,
+            en_passant_square: self.en_passant_square,
+            castle_rights: self.castle_rights,
+            halfmove_clock: self.halfmove_clock,
+            zobrist_key: self.zobrist_key,
+        });
+
+        // captures and pawn moves can't be undone so previous positions can't repeat
+        if end_piece.is_some() || matches!(piece, Some(Piece::Pawn(_))) {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+
+        // remove the old en passant and castle rights from the key
+        self.zobrist_key ^= self.en_passant_key();
+        self.zobrist_key ^= zobrist::castle_rights_key(&self.castle_rights);
+
+        self.set_square(start_i, None);
+        self.set_square(end_i, piece);
         self.en_passant_square = None;
 
         macro_rules! set_rights {
@@ -116,8 +181,8 @@ impl Board {
         if let Some(piece) = piece {
             let piece_color = *piece.get_color();
             let backward_index = match piece_color {
-                Color::White => (end_i + 8),
-                Color::Black => (end_i.saturating_sub(8)),
+                Color::White => end_i + 8,
+                Color::Black => end_i.saturating_sub(8),
             };
 
             match raw_move.flag {
@@ -125,7 +190,7 @@ impl Board {
                     self.en_passant_square = Some(backward_index as u8);
                 }
                 MoveFlag::EnPassantCapture => {
-                    self.grid[backward_index] = None;
+                    self.set_square(backward_index, None);
                 }
                 MoveFlag::Castle(side) => {
                     let first_rank_index = if piece_color == Color::White { 56 } else { 0 };
@@ -135,12 +200,11 @@ impl Board {
                     };
 
                     // move rook
-                    self.grid[first_rank_index + start_file] = None;
-                    self.grid[first_rank_index + end_file] = Some(Piece::Rook(piece_color));
+                    self.set_square(first_rank_index + start_file, None);
+                    self.set_square(first_rank_index + end_file, Some(Piece::Rook(piece_color)));
                 }
                 MoveFlag::Promote(piece) => {
-                    println!("{}", piece);
-                    self.grid[end_i] = Some(piece);
+                    self.set_square(end_i, Some(piece));
                 }
                 _ => (),
             }
@@ -154,52 +218,244 @@ impl Board {
             }
         }
 
-        match end_piece {
-            Some(Piece::Rook(color)) => {
-                set_rights!(end_i, color);
-            }
-            _ => (),
+        if let Some(Piece::Rook(color)) = end_piece {
+            set_rights!(end_i, color);
         }
 
         // change turns
-        self.turn = match self.turn {
-            Color::White => Color::Black,
-            Color::Black => Color::White,
+        if self.turn == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.turn = self.turn.opposite();
+
+        self.zobrist_key ^= zobrist::castle_rights_key(&self.castle_rights);
+        self.zobrist_key ^= zobrist::turn_key();
+        self.zobrist_key ^= self.en_passant_key();
+    }
+
+    /// Unmakes the last move made restoring the board to exactly how it was before.
+    /// Returns the move that was unmade or none if there are no moves to unmake.
+    pub fn unmake_move(&mut self) -> Option<Move> {
+        let undo_info = self.undo_stack.pop()?;
+        let raw_move = undo_info.made_move;
+        let (start_i, end_i) = (raw_move.start_index as usize, raw_move.end_index as usize);
+
+        self.turn = self.turn.opposite();
+        if self.turn == Color::Black {
+            self.fullmove_number -= 1;
+        }
+
+        let piece_color = self.turn;
+        let piece = match raw_move.flag {
+            MoveFlag::Promote(_) => Some(Piece::Pawn(piece_color)),
+            _ => self.grid[end_i],
         };
+        self.set_square(start_i, piece);
+        self.set_square(end_i, undo_info.captured_piece);
+
+        match raw_move.flag {
+            MoveFlag::EnPassantCapture => {
+                let backward_index = match piece_color {
+                    Color::White => end_i + 8,
+                    Color::Black => end_i - 8,
+                };
+                self.set_square(backward_index, Some(Piece::Pawn(piece_color.opposite())));
+            }
+            MoveFlag::Castle(side) => {
+                let first_rank_index = if piece_color == Color::White { 56 } else { 0 };
+                let (start_file, end_file) = match side {
+                    Side::King => (7, 5),
+                    Side::Queen => (0, 3),
+                };
+
+                // move rook back
+                self.set_square(first_rank_index + end_file, None);
+                self.set_square(
+                    first_rank_index + start_file,
+                    Some(Piece::Rook(piece_color)),
+                );
+            }
+            _ => (),
+        }
+
+        self.en_passant_square = undo_info.en_passant_square;
+        self.castle_rights = undo_info.castle_rights;
+        self.halfmove_clock = undo_info.halfmove_clock;
+        self.zobrist_key = undo_info.zobrist_key;
+        Some(raw_move)
+    }
+
+    /// Gets the moves that have been made on the board in order
+    pub fn moves_made(&self) -> impl Iterator<Item = Move> + '_ {
+        self.undo_stack.iter().map(|undo_info| undo_info.made_move)
     }
 
     pub fn move_is_capture(&self, test_move: Move) -> bool {
         self.grid[test_move.end_index as usize].is_some()
-            || self.en_passant_square == Some(test_move.end_index)
+            || (self.en_passant_square == Some(test_move.end_index)
+                && matches!(
+                    self.grid[test_move.start_index as usize],
+                    Some(Piece::Pawn(_))
+                ))
     }
 
     pub fn get_all_legal_moves(&self) -> Vec<Move> {
-        let mut moves = Vec::new();
-        for (i, piece) in self.grid.iter().enumerate() {
-            if let Some(piece) = piece {
-                piece.add_legal_moves(i as u8, &mut moves, &self);
-            }
-        }
-
+        let mut moves = Vec::with_capacity(64);
+        self.add_legal_moves(&mut moves);
         moves
     }
 
     /// Tests if the move is legal and return it with the neccessery flags set (en passant, double push, etc.)
-    /// else it will return none
+    /// else it will return none. Promotions use the piece from the move's flag or a queen if it
+    /// has none.
     pub fn as_legal_move(&self, test_move: Move) -> Option<Move> {
-        if let Some(piece) = self.grid[test_move.start_index as usize] {
-            let legal_moves = piece.get_legal_moves(test_move.start_index, &self);
-            let legal_move = legal_moves
-                .iter()
-                .find(|legal_move| legal_move.end_index == test_move.end_index);
+        // promote to a queen if the piece isn't given
+        let promote_kind = match test_move.flag {
+            MoveFlag::Promote(piece) => piece.kind_index(),
+            _ => Piece::QUEEN,
+        };
 
-            legal_move.copied()
+        self.get_all_legal_moves().into_iter().find(|legal_move| {
+            legal_move.start_index == test_move.start_index
+                && legal_move.end_index == test_move.end_index
+                && match legal_move.flag {
+                    MoveFlag::Promote(piece) => piece.kind_index() == promote_kind,
+                    _ => !matches!(test_move.flag, MoveFlag::Promote(_)),
+                }
+        })
+    }
+
+    /// Gets the status of the game from the side to move's perspective (checkmate, stalemate, etc.)
+    pub fn status(&self) -> GameStatus {
+        if self.get_all_legal_moves().is_empty() {
+            return if self.in_check() {
+                GameStatus::Checkmate(self.turn.opposite())
+            } else {
+                GameStatus::Stalemate
+            };
+        }
+
+        if self.is_insufficient_material() {
+            GameStatus::Draw(DrawReason::InsufficientMaterial)
+        } else if self.repetition_count() >= 5 {
+            GameStatus::Draw(DrawReason::FivefoldRepetition)
+        } else if self.halfmove_clock >= 150 {
+            GameStatus::Draw(DrawReason::SeventyFiveMoveRule)
+        } else {
+            GameStatus::Ongoing
+        }
+    }
+
+    /// Gets the draw a player can claim (threefold repetition or the fifty move rule) when the
+    /// game isn't already over. These don't end the game until they are claimed.
+    pub fn claimable_draw(&self) -> Option<DrawReason> {
+        if self.status().is_over() {
+            None
+        } else if self.repetition_count() >= 3 {
+            Some(DrawReason::ThreefoldRepetition)
+        } else if self.halfmove_clock >= 100 {
+            Some(DrawReason::FiftyMoveRule)
         } else {
             None
         }
     }
+
+    /// Gets the number of times the current position has occurred including now
+    pub fn repetition_count(&self) -> usize {
+        // positions before the last capture or pawn move can't be repeated
+        1 + self
+            .undo_stack
+            .iter()
+            .rev()
+            .take(self.halfmove_clock as usize)
+            .filter(|undo_info| undo_info.zobrist_key == self.zobrist_key)
+            .count()
+    }
+
+    /// Checks if neither side has enough pieces left to checkmate (K vs K, KB vs K, KN vs K or
+    /// kings with bishops that are all on the same colour square)
+    pub fn is_insufficient_material(&self) -> bool {
+        let major_pieces =
+            self.pieces[Piece::PAWN] | self.pieces[Piece::ROOK] | self.pieces[Piece::QUEEN];
+        if !major_pieces.is_empty() {
+            return false;
+        }
+
+        let bishops = self.pieces[Piece::BISHOP];
+        let num_knights = self.pieces[Piece::KNIGHT].count();
+        let on_light_squares = !(bishops & LIGHT_SQUARES).is_empty();
+        let on_dark_squares = !(bishops & !LIGHT_SQUARES).is_empty();
+        match (on_light_squares, on_dark_squares) {
+            (false, false) => num_knights <= 1,
+            (true, true) => false,
+            _ => num_knights == 0,
+        }
+    }
+
+    /// Gets the index of the king with the specified colour if there is one
+    pub fn find_king(&self, color: Color) -> Option<u8> {
+        self.pieces(Piece::King(color)).lsb()
+    }
+
+    /// Checks if the square at index is attacked by any piece of the specified colour
+    pub fn is_square_attacked(&self, index: u8, by_color: Color) -> bool {
+        !self
+            .attackers_with_occupancy(index, by_color, self.occupancy())
+            .is_empty()
+    }
+
+    /// Gets the indices of all the pieces of the specified colour that attack the square at index
+    pub fn attackers_to(&self, index: u8, by_color: Color) -> Vec<u8> {
+        self.attackers_with_occupancy(index, by_color, self.occupancy())
+            .collect()
+    }
+
+    /// Checks if the king of the side to move is in check
+    pub fn in_check(&self) -> bool {
+        self.find_king(self.turn)
+            .is_some_and(|king_index| self.is_square_attacked(king_index, self.turn.opposite()))
+    }
+
+    /// Gets the indices of all the pieces giving check to the king of the side to move
+    pub fn checkers(&self) -> Vec<u8> {
+        self.find_king(self.turn)
+            .map_or_else(Vec::new, |king_index| {
+                self.attackers_to(king_index, self.turn.opposite())
+            })
+    }
+
+    /// Checks if a pawn of the side to move is next to the pawn that just double pushed
+    fn can_capture_en_passant(&self) -> bool {
+        self.en_passant_square.is_some_and(|en_passant_square| {
+            let attackers = bitboard::pawn_attacks(en_passant_square, self.turn.opposite());
+            !(attackers & self.pieces(Piece::Pawn(self.turn))).is_empty()
+        })
+    }
+
+    /// Gets the pieces of the specified colour that attack the square at index with the pieces on
+    /// the board limited to the occupancy. Pieces can be moved out of the way (or taken off) by
+    /// leaving them out of the occupancy.
+    pub(crate) fn attackers_with_occupancy(
+        &self,
+        index: u8,
+        by_color: Color,
+        occupancy: Bitboard,
+    ) -> Bitboard {
+        let queens = self.pieces[Piece::QUEEN];
+        let attackers = (bitboard::knight_attacks(index) & self.pieces[Piece::KNIGHT])
+            | (bitboard::king_attacks(index) & self.pieces[Piece::KING])
+            // a pawn attacks the square if it is where an opposite pawn on the square would attack
+            | (bitboard::pawn_attacks(index, by_color.opposite()) & self.pieces[Piece::PAWN])
+            | (bitboard::rook_attacks(index, occupancy) & (self.pieces[Piece::ROOK] | queens))
+            | (bitboard::bishop_attacks(index, occupancy) & (self.pieces[Piece::BISHOP] | queens));
+
+        attackers & self.colors[by_color.as_index()] & occupancy
+    }
 }
 
+// Squares where the file and rank add up to an even number starting with a8
+const LIGHT_SQUARES: Bitboard = Bitboard(0xAA55_AA55_AA55_AA55);
+
 impl Default for Board {
     fn default() -> Self {
         Self::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap()
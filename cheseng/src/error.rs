@@ -1,19 +1,22 @@
+use crate::Color;
+
 pub enum Error {
-    InvalidFEN,
-    InvalidFENStr(String),
+    InvalidFEN(FenError),
+    InvalidSAN(SanError),
+    InvalidPGN(PgnError),
     OutsideBounds(crate::Position),
     InvalidPosition(Option<char>),
+    InvalidMove(Option<char>),
 }
 
 impl std::fmt::Debug for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Self::InvalidFEN => {
-                write!(f, "InvalidFEN: Invalid number of sections or subsections")
-            }
-            Self::InvalidFENStr(string) => {
-                write!(f, "InvalidFEN: Unexpected string: {}", string)
+            Self::InvalidFEN(fen_error) => {
+                write!(f, "InvalidFEN: {:?}", fen_error)
             }
+            Self::InvalidSAN(san_error) => write!(f, "InvalidSAN: {:?}", san_error),
+            Self::InvalidPGN(pgn_error) => write!(f, "InvalidPGN: {:?}", pgn_error),
             Self::OutsideBounds(position) => write!(
                 f,
                 "OustideBounds: {:?} is ousisde 8x8 chess board",
@@ -25,6 +28,123 @@ impl std::fmt::Debug for Error {
             Self::InvalidPosition(None) => {
                 write!(f, "InvalidPosition: Expected 2 chars")
             }
+            Self::InvalidMove(Some(char)) => {
+                write!(f, "InvalidMove: Unexpected char: {}", char)
+            }
+            Self::InvalidMove(None) => {
+                write!(f, "InvalidMove: Expected 4 or 5 chars")
+            }
         }
     }
 }
+
+/// Why a move in Standard Algebraic Notation couldn't be turned into a legal move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SanError {
+    /// Isn't written like a SAN move
+    InvalidNotation,
+    /// No legal move matches the notation
+    IllegalMove,
+    /// More than one legal move matches the notation so the start square needs to be given
+    AmbiguousMove,
+}
+
+/// The whitespace separated sections of a FEN string in order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FenField {
+    Placement,
+    Turn,
+    CastleRights,
+    EnPassant,
+    HalfmoveClock,
+    FullmoveNumber,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FenErrorKind {
+    MissingField,
+    TooManyFields,
+    UnexpectedChar(char),
+    UnexpectedStr(String),
+    /// A row in the piece placement that doesn't add up to 8 squares
+    InvalidRowLength,
+    InvalidNumRows,
+    PawnOnBackRank,
+    InvalidNumKings(Color),
+    /// The side not to move is in check so the king could be captured
+    OpponentInCheck,
+    /// A castle right is repeated, out of order or the king or rook isn't on its starting square
+    InvalidCastleRight(char),
+    /// The en passant square doesn't come from a pawn double push
+    InvalidEnPassant,
+}
+
+/// An error in a FEN string with the field it happened in and the char offset into the string
+#[derive(Clone, PartialEq)]
+pub struct FenError {
+    pub field: FenField,
+    pub offset: usize,
+    pub kind: FenErrorKind,
+}
+
+impl std::fmt::Debug for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} in {:?} field at char {}",
+            self.kind, self.field, self.offset
+        )
+    }
+}
+
+impl From<FenError> for Error {
+    fn from(fen_error: FenError) -> Self {
+        Self::InvalidFEN(fen_error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgnErrorKind {
+    UnexpectedChar(char),
+    /// The PGN ended inside a comment or variation
+    UnexpectedEnd,
+    InvalidTag,
+    InvalidFen(FenError),
+    /// A move that isn't legal or can't be read as SAN
+    InvalidMove(String, SanError),
+    /// A NAG that isn't a number from 0 to 255 or an unknown suffix annotation
+    InvalidNag,
+    NagWithoutMove,
+    /// A variation that doesn't come after a move it can replace
+    VariationWithoutMove,
+    EmptyVariation,
+    /// A game result inside a variation
+    UnexpectedResult,
+    /// The starting board couldn't be set up or a move couldn't be read for a reason other than
+    /// the FEN or SAN, with the error that caused it
+    InvalidGame(String),
+}
+
+/// An error in a PGN string with the line and column (starting from 1) where it happened
+#[derive(Clone, PartialEq)]
+pub struct PgnError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: PgnErrorKind,
+}
+
+impl std::fmt::Debug for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} at line {} column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl From<PgnError> for Error {
+    fn from(pgn_error: PgnError) -> Self {
+        Self::InvalidPGN(pgn_error)
+    }
+}
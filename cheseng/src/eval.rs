@@ -0,0 +1,152 @@
+use crate::{Board, Color};
+
+/// Value of each type of piece indexed by [crate::Piece::kind_index]
+pub(crate) const PIECE_VALUES: [i32; 6] = [100, 320, 330, 500, 900, 0];
+
+/// How much each non-pawn piece counts towards the game being in the middlegame indexed by
+/// [crate::Piece::kind_index]. All the pieces at the start add up to [MAX_PHASE].
+const PHASE_WEIGHTS: [i32; 6] = [0, 1, 1, 2, 4, 0];
+const MAX_PHASE: i32 = 24;
+
+// Bonuses for where each piece is from white's point of view with a8 first like the board. Black
+// pieces use the square flipped vertically.
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+/// The king should hide behind its pawns while there are pieces to attack it
+#[rustfmt::skip]
+const KING_MIDDLEGAME_TABLE: [i32; 64] = [
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+     20, 20,  0,  0,  0,  0, 20, 20,
+     20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+/// The king should come to the centre once most of the pieces are gone
+#[rustfmt::skip]
+const KING_ENDGAME_TABLE: [i32; 64] = [
+    -50,-40,-30,-20,-20,-30,-40,-50,
+    -30,-20,-10,  0,  0,-10,-20,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-30,  0,  0,  0,  0,-30,-30,
+    -50,-30,-30,-30,-30,-30,-30,-50,
+];
+
+const PIECE_TABLES: [&[i32; 64]; 5] = [
+    &PAWN_TABLE,
+    &KNIGHT_TABLE,
+    &BISHOP_TABLE,
+    &ROOK_TABLE,
+    &QUEEN_TABLE,
+];
+
+impl Board {
+    /// Gets a score for the position in centipawns from the side to move's point of view using
+    /// material and where the pieces are. Checkmate and draws aren't looked at.
+    pub fn evaluate(&self) -> i32 {
+        let phase: i32 = self
+            .pieces
+            .iter()
+            .zip(PHASE_WEIGHTS)
+            .map(|(pieces, weight)| pieces.count() as i32 * weight)
+            .sum();
+        let phase = phase.min(MAX_PHASE);
+
+        let mut score = 0;
+        for color in [Color::White, Color::Black] {
+            let sign = match color {
+                Color::White => 1,
+                Color::Black => -1,
+            };
+            // the tables are from white's point of view
+            let flip = match color {
+                Color::White => 0,
+                Color::Black => 56,
+            };
+            let own = self.colors[color.as_index()];
+
+            for (kind_index, table) in PIECE_TABLES.iter().enumerate() {
+                for index in self.pieces[kind_index] & own {
+                    score += sign * (PIECE_VALUES[kind_index] + table[(index ^ flip) as usize]);
+                }
+            }
+
+            if let Some(king_index) = self.find_king(color) {
+                let square = (king_index ^ flip) as usize;
+                score += sign
+                    * (KING_MIDDLEGAME_TABLE[square] * phase
+                        + KING_ENDGAME_TABLE[square] * (MAX_PHASE - phase))
+                    / MAX_PHASE;
+            }
+        }
+
+        match self.turn {
+            Color::White => score,
+            Color::Black => -score,
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use crate::error::{FenError, FenErrorKind, FenField};
+use crate::{Board, Color, Error, Piece, Position};
+
+impl Board {
+    /// Creates a new board from Forsyth-Edwards Notation.
+    /// The halfmove clock and fullmove number are optional and default to 0 and 1.
+    /// Positions that can't be reached (missing kings, pawns on the back rank, etc.) are rejected.
+    pub fn from_fen(fen: &str) -> Result<Self, Error> {
+        let mut board = Self::empty();
+        let fields = split_fields(fen);
+        let field = |index: usize, field: FenField| {
+            fields.get(index).copied().ok_or(FenError {
+                field,
+                offset: fen.chars().count(),
+                kind: FenErrorKind::MissingField,
+            })
+        };
+
+        let (offset, placement_str) = field(0, FenField::Placement)?;
+        parse_placement(&mut board, placement_str, offset)?;
+
+        let (turn_offset, turn_str) = field(1, FenField::Turn)?;
+        board.turn = match turn_str {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => Err(FenError {
+                field: FenField::Turn,
+                offset: turn_offset,
+                kind: FenErrorKind::UnexpectedStr(turn_str.into()),
+            })?,
+        };
+
+        let (offset, castle_rights_str) = field(2, FenField::CastleRights)?;
+        parse_castle_rights(&mut board, castle_rights_str, offset)?;
+
+        let (offset, en_passant_str) = field(3, FenField::EnPassant)?;
+        parse_en_passant(&mut board, en_passant_str, offset)?;
+
+        let parse_number = |index: usize, field: FenField, min: u32| match fields.get(index) {
+            Some(&(offset, number_str)) => number_str
+                .parse()
+                .ok()
+                .filter(|&number| number >= min)
+                .ok_or(FenError {
+                    field,
+                    offset,
+                    kind: FenErrorKind::UnexpectedStr(number_str.into()),
+                })
+                .map(Some),
+            None => Ok(None),
+        };
+
+        if let Some(halfmove_clock) = parse_number(4, FenField::HalfmoveClock, 0)? {
+            board.halfmove_clock = halfmove_clock;
+        }
+
+        if let Some(fullmove_number) = parse_number(5, FenField::FullmoveNumber, 1)? {
+            board.fullmove_number = fullmove_number;
+        }
+
+        if let Some(&(offset, _)) = fields.get(6) {
+            Err(FenError {
+                field: FenField::FullmoveNumber,
+                offset,
+                kind: FenErrorKind::TooManyFields,
+            })?;
+        }
+
+        // the side to move can't be able to capture the king
+        let opponent_color = board.turn.opposite();
+        if let Some(king_index) = board.find_king(opponent_color) {
+            if board.is_square_attacked(king_index, board.turn) {
+                Err(FenError {
+                    field: FenField::Turn,
+                    offset: turn_offset,
+                    kind: FenErrorKind::OpponentInCheck,
+                })?;
+            }
+        }
+
+        board.update_zobrist_key();
+        Ok(board)
+    }
+
+    /// Converts the board into Forsyth-Edwards Notation.
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+        for (rank, row) in self.grid().chunks(8).enumerate() {
+            if rank != 0 {
+                fen.push('/');
+            }
+
+            let mut num_empty = 0;
+            for piece in row {
+                if let Some(piece) = piece {
+                    if num_empty != 0 {
+                        fen.push_str(&num_empty.to_string());
+                        num_empty = 0;
+                    }
+                    fen.push(piece.get_fen_char());
+                } else {
+                    num_empty += 1;
+                }
+            }
+
+            if num_empty != 0 {
+                fen.push_str(&num_empty.to_string());
+            }
+        }
+
+        fen.push_str(match self.turn {
+            Color::White => " w ",
+            Color::Black => " b ",
+        });
+
+        let castle_rights_str: String = [
+            (self.castle_rights[0][1], 'K'),
+            (self.castle_rights[0][0], 'Q'),
+            (self.castle_rights[1][1], 'k'),
+            (self.castle_rights[1][0], 'q'),
+        ]
+        .iter()
+        .filter_map(|&(has_right, char)| has_right.then_some(char))
+        .collect();
+
+        if castle_rights_str.is_empty() {
+            fen.push('-');
+        } else {
+            fen.push_str(&castle_rights_str);
+        }
+
+        match self.en_passant_square {
+            Some(index) => fen.push_str(&format!(" {}", Position::from_index(index))),
+            None => fen.push_str(" -"),
+        }
+
+        fen.push_str(&format!(
+            " {} {}",
+            self.halfmove_clock, self.fullmove_number
+        ));
+        fen
+    }
+}
+
+/// Splits the string by whitespace into fields with the char offset of the start of each field
+fn split_fields(fen: &str) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut field_start = None;
+    for (char_offset, (byte_offset, char)) in fen.char_indices().enumerate() {
+        match (char.is_whitespace(), field_start) {
+            (false, None) => field_start = Some((char_offset, byte_offset)),
+            (true, Some((start_char_offset, start_byte_offset))) => {
+                fields.push((start_char_offset, &fen[start_byte_offset..byte_offset]));
+                field_start = None;
+            }
+            _ => (),
+        }
+    }
+
+    if let Some((start_char_offset, start_byte_offset)) = field_start {
+        fields.push((start_char_offset, &fen[start_byte_offset..]));
+    }
+
+    fields
+}
+
+fn parse_placement(board: &mut Board, placement_str: &str, offset: usize) -> Result<(), FenError> {
+    let error = |char_index: usize, kind: FenErrorKind| FenError {
+        field: FenField::Placement,
+        offset: offset + char_index,
+        kind,
+    };
+
+    let (mut file, mut rank) = (0, 0);
+    let mut last_was_digit = false;
+    let mut king_offsets: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+    for (char_index, char) in placement_str.chars().enumerate() {
+        match char {
+            '/' => {
+                if file != 8 {
+                    Err(error(char_index, FenErrorKind::InvalidRowLength))?;
+                }
+
+                rank += 1;
+                file = 0;
+                if rank > 7 {
+                    Err(error(char_index, FenErrorKind::InvalidNumRows))?;
+                }
+            }
+
+            '1'..='8' => {
+                // empty squares should be added together (eg. 8 instead of 44)
+                if last_was_digit {
+                    Err(error(char_index, FenErrorKind::UnexpectedChar(char)))?;
+                }
+
+                file += char.to_digit(10).unwrap() as usize;
+                if file > 8 {
+                    Err(error(char_index, FenErrorKind::InvalidRowLength))?;
+                }
+            }
+
+            _ => {
+                let piece = Piece::from_fen_char(char)
+                    .ok_or(error(char_index, FenErrorKind::UnexpectedChar(char)))?;
+
+                if file > 7 {
+                    Err(error(char_index, FenErrorKind::InvalidRowLength))?;
+                }
+
+                match piece {
+                    Piece::Pawn(_) if rank == 0 || rank == 7 => {
+                        Err(error(char_index, FenErrorKind::PawnOnBackRank))?
+                    }
+                    Piece::King(color) => king_offsets[color.as_index()].push(char_index),
+                    _ => (),
+                }
+
+                board.set_piece((rank * 8 + file) as u8, Some(piece));
+                file += 1;
+            }
+        }
+
+        last_was_digit = char.is_ascii_digit();
+    }
+
+    let end_index = placement_str.chars().count();
+    if rank != 7 {
+        Err(error(end_index, FenErrorKind::InvalidNumRows))?;
+    } else if file != 8 {
+        Err(error(end_index, FenErrorKind::InvalidRowLength))?;
+    }
+
+    // each side needs exactly one king
+    for color in [Color::White, Color::Black] {
+        match king_offsets[color.as_index()][..] {
+            [_] => (),
+            [] => Err(error(0, FenErrorKind::InvalidNumKings(color)))?,
+            [_, extra_king_index, ..] => Err(error(
+                extra_king_index,
+                FenErrorKind::InvalidNumKings(color),
+            ))?,
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_castle_rights(
+    board: &mut Board,
+    castle_rights_str: &str,
+    offset: usize,
+) -> Result<(), FenError> {
+    if castle_rights_str == "-" {
+        return Ok(());
+    }
+
+    // rights must be in the order KQkq without repeats
+    let mut min_order_index = 0;
+    for (char_index, char) in castle_rights_str.chars().enumerate() {
+        let error = |kind: FenErrorKind| FenError {
+            field: FenField::CastleRights,
+            offset: offset + char_index,
+            kind,
+        };
+
+        let order_index = "KQkq"
+            .find(char)
+            .ok_or(error(FenErrorKind::UnexpectedChar(char)))?;
+        if order_index < min_order_index {
+            Err(error(FenErrorKind::InvalidCastleRight(char)))?;
+        }
+        min_order_index = order_index + 1;
+
+        let (color, side_index, rook_file) = match char {
+            'K' => (Color::White, 1, 7),
+            'Q' => (Color::White, 0, 0),
+            'k' => (Color::Black, 1, 7),
+            _ => (Color::Black, 0, 0),
+        };
+
+        // king and rook need to be on their starting squares
+        let first_rank_index = if color == Color::White { 56 } else { 0 };
+        if board.grid()[first_rank_index + 4] != Some(Piece::King(color))
+            || board.grid()[first_rank_index + rook_file] != Some(Piece::Rook(color))
+        {
+            Err(error(FenErrorKind::InvalidCastleRight(char)))?;
+        }
+
+        board.castle_rights[color.as_index()][side_index] = true;
+    }
+
+    Ok(())
+}
+
+fn parse_en_passant(
+    board: &mut Board,
+    en_passant_str: &str,
+    offset: usize,
+) -> Result<(), FenError> {
+    if en_passant_str == "-" {
+        return Ok(());
+    }
+
+    let error = |kind: FenErrorKind| FenError {
+        field: FenField::EnPassant,
+        offset,
+        kind,
+    };
+
+    let en_passant_square = en_passant_str
+        .parse::<Position>()
+        .and_then(|position| position.as_index())
+        .or(Err(error(FenErrorKind::UnexpectedStr(
+            en_passant_str.into(),
+        ))))? as usize;
+
+    // the pawn that double pushed should be in front of the square with the square it came from
+    // and the square itself empty
+    let (ep_rank, forward_offset) = match board.turn {
+        Color::White => (2, 8),
+        Color::Black => (5, -8),
+    };
+
+    let pushed_pawn_index = (en_passant_square as isize + forward_offset) as usize;
+    let start_index = (en_passant_square as isize - forward_offset) as usize;
+    if en_passant_square / 8 != ep_rank
+        || board.grid()[pushed_pawn_index] != Some(Piece::Pawn(board.turn.opposite()))
+        || board.grid()[en_passant_square].is_some()
+        || board.grid()[start_index].is_some()
+    {
+        Err(error(FenErrorKind::InvalidEnPassant))?;
+    }
+
+    board.en_passant_square = Some(en_passant_square as u8);
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use crate::pgn::{GameResult, PgnGame, PgnNode};
+use crate::{Board, Color, DrawReason, Error, GameStatus, Move};
+
+/// A game from its starting position with the moves played, the position at each ply, tags
+/// (players, event, date, etc.) and the result. Going back to an earlier ply keeps the later
+/// moves until a different move is made.
+#[derive(Clone)]
+pub struct Game {
+    starting_board: Board,
+    /// The board at the current ply
+    board: Board,
+    moves: Vec<Move>,
+    /// Zobrist key of the position at each ply starting with the starting position
+    hashes: Vec<u64>,
+    ply: usize,
+    pub tags: Vec<(String, String)>,
+    result: GameResult,
+}
+
+/// The tags every game has in the order they are written along with the value used when they
+/// aren't known
+pub(crate) const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+impl Game {
+    /// Creates a game from the starting position with the seven tag roster filled with unknown
+    /// values
+    pub fn new() -> Self {
+        Self::from_board(Board::default())
+    }
+
+    /// Creates a game from the position without any moves
+    pub fn from_fen(fen: &str) -> Result<Self, Error> {
+        Ok(Self::from_board(Board::from_fen(fen)?))
+    }
+
+    /// Creates a game where the moves already made on the board are the moves of the game
+    pub fn from_board(board: Board) -> Self {
+        let mut starting_board = board.clone();
+        while starting_board.unmake_move().is_some() {}
+
+        let mut game = Self {
+            hashes: vec![starting_board.zobrist_key()],
+            board: starting_board.clone(),
+            starting_board,
+            moves: Vec::new(),
+            ply: 0,
+            tags: SEVEN_TAG_ROSTER
+                .iter()
+                .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+            result: GameResult::Unknown,
+        };
+
+        for made_move in board.moves_made() {
+            game.make_move(made_move);
+        }
+
+        game
+    }
+
+    /// Creates a game from the main line of a PGN game
+    pub fn from_pgn(pgn_game: &PgnGame) -> Result<Self, Error> {
+        let mut game = Self::from_board(pgn_game.starting_board()?);
+        for node in &pgn_game.moves {
+            game.make_move(node.chess_move);
+        }
+
+        // the starting position is kept in the starting board instead
+        game.tags = pgn_game
+            .tags
+            .iter()
+            .filter(|(name, _)| name != "SetUp" && name != "FEN")
+            .cloned()
+            .collect();
+        game.set_result(pgn_game.result);
+        Ok(game)
+    }
+
+    /// Creates a PGN game from the moves up to the last ply. The starting position is added as a
+    /// FEN tag if it isn't the default.
+    pub fn to_pgn(&self) -> PgnGame {
+        let mut tags: Vec<(String, String)> = self
+            .tags
+            .iter()
+            .filter(|(name, _)| name != "SetUp" && name != "FEN")
+            .cloned()
+            .collect();
+
+        let fen = self.starting_board.to_fen();
+        if fen != Board::default().to_fen() {
+            tags.push(("SetUp".to_owned(), "1".to_owned()));
+            tags.push(("FEN".to_owned(), fen));
+        }
+
+        let mut board = self.starting_board.clone();
+        let moves = self
+            .moves
+            .iter()
+            .map(|&chess_move| {
+                let node = PgnNode::new(chess_move, board.move_to_san(chess_move));
+                board.make_move(chess_move);
+                node
+            })
+            .collect();
+
+        PgnGame {
+            tags,
+            moves,
+            result: self.result,
+        }
+    }
+
+    /// Gets the board at the current ply
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Gets the board before any moves are made
+    pub fn starting_board(&self) -> &Board {
+        &self.starting_board
+    }
+
+    /// Gets every move of the game including the ones after the current ply
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Gets the Zobrist key of the position at each ply starting with the starting position
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// Gets the number of halfmoves made to get to the current position
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Gets the number of halfmoves in the game including the ones after the current ply
+    pub fn num_plies(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Gets the board at the ply or none if the game doesn't have that many halfmoves
+    pub fn board_at(&self, ply: usize) -> Option<Board> {
+        let mut board = self.starting_board.clone();
+        for &chess_move in self.moves.get(..ply)? {
+            board.make_move(chess_move);
+        }
+
+        Some(board)
+    }
+
+    /// Makes a legal move at the current ply. Any moves after the current ply are removed and
+    /// the result is set when the move ends the game.
+    pub fn make_move(&mut self, legal_move: Move) {
+        self.moves.truncate(self.ply);
+        self.hashes.truncate(self.ply + 1);
+
+        self.board.make_move(legal_move);
+        self.moves.push(legal_move);
+        self.hashes.push(self.board.zobrist_key());
+        self.ply += 1;
+
+        self.set_result(self.status_result());
+    }
+
+    /// Takes back the move that led to the current position, removing it along with any moves
+    /// after it. Returns the move taken back or none at the start of the game.
+    pub fn take_back(&mut self) -> Option<Move> {
+        let taken_back = *self.moves.get(self.ply.checked_sub(1)?)?;
+        self.board.unmake_move();
+        self.ply -= 1;
+        self.moves.truncate(self.ply);
+        self.hashes.truncate(self.ply + 1);
+        self.set_result(self.status_result());
+        Some(taken_back)
+    }
+
+    /// Goes to the position after the number of halfmoves without removing any moves. Returns
+    /// false if the game doesn't have that many halfmoves.
+    pub fn go_to_ply(&mut self, ply: usize) -> bool {
+        if ply > self.moves.len() {
+            return false;
+        }
+
+        while self.ply > ply {
+            self.board.unmake_move();
+            self.ply -= 1;
+        }
+        while self.ply < ply {
+            self.board.make_move(self.moves[self.ply]);
+            self.ply += 1;
+        }
+
+        true
+    }
+
+    pub fn result(&self) -> GameResult {
+        self.result
+    }
+
+    /// Sets the result of the game (eg. when a player resigns) and its Result tag
+    pub fn set_result(&mut self, result: GameResult) {
+        self.result = result;
+        self.set_tag("Result", &result.to_string());
+    }
+
+    /// Gets the status of the game at the current ply
+    pub fn status(&self) -> GameStatus {
+        self.board.status()
+    }
+
+    /// Gets the draw a player can claim in the current position (see [Board::claimable_draw])
+    pub fn claimable_draw(&self) -> Option<DrawReason> {
+        self.board.claimable_draw()
+    }
+
+    /// Gets the value of the first tag with the name
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of the tag adding it if the game doesn't have it
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some((_, tag_value)) => *tag_value = value.to_owned(),
+            None => self.tags.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    /// Gets the result from the status of the current position which should be after the last
+    /// move
+    fn status_result(&self) -> GameResult {
+        match self.board.status() {
+            GameStatus::Checkmate(Color::White) => GameResult::WhiteWins,
+            GameStatus::Checkmate(Color::Black) => GameResult::BlackWins,
+            GameStatus::Stalemate | GameStatus::Draw(_) => GameResult::Draw,
+            GameStatus::Ongoing => GameResult::Unknown,
+        }
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,11 +1,29 @@
+mod bitboard;
 mod board;
 mod error;
+mod eval;
+mod fen;
+mod game;
 mod r#move;
+mod move_picker;
+mod movegen;
+pub mod perft;
+pub mod pgn;
 mod piece;
 mod position;
+mod san;
+pub mod search;
+mod see;
+mod status;
+mod time_manager;
+mod transposition_table;
+mod zobrist;
 
+pub use bitboard::Bitboard;
 pub use board::Board;
-pub use error::Error;
+pub use error::{Error, FenError, FenErrorKind, FenField, PgnError, PgnErrorKind, SanError};
+pub use game::Game;
 pub use piece::{Color, Piece};
 pub use position::{pos, Position};
 pub use r#move::{Move, MoveFlag, Side};
+pub use status::{DrawReason, GameStatus};
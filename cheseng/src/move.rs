@@ -1,4 +1,4 @@
-use crate::{Error, Piece, Position};
+use crate::{Color, Error, Piece, Position};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Side {
@@ -15,7 +15,7 @@ impl Side {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MoveFlag {
     None,
     EnPassantCapture,
@@ -25,7 +25,7 @@ pub enum MoveFlag {
     PromoteKnight,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Move {
     pub start_index: u8,
     pub end_index: u8,
@@ -49,14 +49,62 @@ impl Move {
     }
 }
 
+/// Parses long algebraic notation as used by UCI (eg. "e2e4" or "e7e8q" to promote to a queen).
+/// Only the promotion flag is set so use [crate::Board::as_legal_move] to get the other flags.
 impl std::str::FromStr for Move {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (pos1, pos2) = s.split_at(2);
-        Ok(Move::new(
-            pos1.parse::<Position>()?.as_index()?,
-            pos2.parse::<Position>()?.as_index()?,
-        ))
+        // the start and end square and an optional promotion piece
+        if !(4..=5).contains(&s.chars().count()) {
+            return Err(Error::InvalidMove(None));
+        }
+
+        // split on chars instead of bytes so unexpected input can't panic
+        let split_index = |s: &str| s.char_indices().nth(2).map_or(s.len(), |(i, _)| i);
+        let (start_str, rest) = s.split_at(split_index(s));
+        let (end_str, promote_str) = rest.split_at(split_index(rest));
+
+        let start_index = start_str.parse::<Position>()?.as_index()?;
+        let end_index = end_str.parse::<Position>()?.as_index()?;
+        let mut parsed_move = Move::new(start_index, end_index);
+
+        if let Some(promote_char) = promote_str.chars().next() {
+            // the colour isn't written so go by which side of the board the pawn promotes on
+            let color = if end_index / 8 == 0 {
+                Color::White
+            } else {
+                Color::Black
+            };
+
+            let piece = match promote_char.to_ascii_lowercase() {
+                'q' => Piece::Queen(color),
+                'r' => Piece::Rook(color),
+                'b' => Piece::Bishop(color),
+                'n' => Piece::Knight(color),
+                _ => Err(Error::InvalidMove(Some(promote_char)))?,
+            };
+            parsed_move = parsed_move.flag(MoveFlag::Promote(piece));
+        }
+
+        Ok(parsed_move)
+    }
+}
+
+/// Writes the move in long algebraic notation as used by UCI (eg. "e2e4" or "e7e8q")
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            Position::from_index(self.start_index),
+            Position::from_index(self.end_index)
+        )?;
+
+        if let MoveFlag::Promote(piece) = self.flag {
+            write!(f, "{}", piece.get_fen_char().to_ascii_lowercase())?;
+        }
+
+        Ok(())
     }
 }
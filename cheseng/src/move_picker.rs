@@ -0,0 +1,286 @@
+use crate::eval::PIECE_VALUES;
+use crate::search::MAX_PLY;
+use crate::{Board, Move, MoveFlag, Piece};
+
+/// History scores stay between -MAX_HISTORY and MAX_HISTORY
+const MAX_HISTORY: i32 = 16384;
+
+/// The order moves are given out in. Each stage is only started once the moves before it didn't
+/// cause a cutoff so the work for later stages is often skipped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Stage {
+    TableMove,
+    Captures,
+    Killers,
+    CounterMove,
+    ScoreQuiets,
+    Quiets,
+    BadCaptures,
+    Done,
+}
+
+/// What the search has learnt about which quiet moves cause cutoffs
+pub(crate) struct MoveOrdering {
+    /// Quiet moves that caused a cutoff at each ply, most recent first. Moves that refute one
+    /// position often refute its siblings too.
+    killers: [[Option<Move>; 2]; MAX_PLY as usize],
+    /// The quiet move that caused a cutoff in reply to the last move indexed by the colour and
+    /// kind of the piece that moved and where it moved to
+    counter_moves: [[[Option<Move>; 64]; 6]; 2],
+    /// How often a quiet move caused a cutoff indexed by the colour moving and the start and
+    /// end square (butterfly boards)
+    history: Box<[[[i32; 64]; 64]; 2]>,
+}
+
+impl MoveOrdering {
+    pub fn new() -> Self {
+        Self {
+            killers: [[None; 2]; MAX_PLY as usize],
+            counter_moves: [[[None; 64]; 6]; 2],
+            history: Box::new([[[0; 64]; 64]; 2]),
+        }
+    }
+
+    /// Forgets everything (eg. for a new game)
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Clears the killers which were for other positions and makes the history from earlier
+    /// searches count for less
+    pub fn new_search(&mut self) {
+        self.killers = [[None; 2]; MAX_PLY as usize];
+        for score in self.history.iter_mut().flatten().flatten() {
+            *score /= 2;
+        }
+    }
+
+    /// Remembers the quiet move that caused a cutoff and makes the quiet moves that were tried
+    /// before it less likely to be tried first next time
+    pub fn on_quiet_cutoff(
+        &mut self,
+        board: &Board,
+        ply: u32,
+        depth: u32,
+        cutoff_move: Move,
+        tried_quiets: &[Move],
+    ) {
+        let killers = &mut self.killers[ply as usize];
+        if killers[0] != Some(cutoff_move) {
+            killers[1] = killers[0];
+            killers[0] = Some(cutoff_move);
+        }
+
+        if let Some(counter_move) = self.counter_move_slot(board) {
+            *counter_move = Some(cutoff_move);
+        }
+
+        let bonus = (depth * depth).min(MAX_HISTORY as u32 / 4) as i32;
+        self.update_history(board, cutoff_move, bonus);
+        for &tried_move in tried_quiets {
+            self.update_history(board, tried_move, -bonus);
+        }
+    }
+
+    fn history(&self, board: &Board, quiet_move: Move) -> i32 {
+        self.history[board.turn.as_index()][quiet_move.start_index as usize]
+            [quiet_move.end_index as usize]
+    }
+
+    /// Moves the score towards the bonus by less the closer it is to the limit so it stays in
+    /// range and newer cutoffs count for more
+    fn update_history(&mut self, board: &Board, quiet_move: Move, bonus: i32) {
+        let score = &mut self.history[board.turn.as_index()][quiet_move.start_index as usize]
+            [quiet_move.end_index as usize];
+        *score += bonus - *score * bonus.abs() / MAX_HISTORY;
+    }
+
+    fn counter_move_slot(&mut self, board: &Board) -> Option<&mut Option<Move>> {
+        let last_move = board.moves_made().last()?;
+        let piece = board.piece_at(last_move.end_index)?;
+        Some(
+            &mut self.counter_moves[piece.get_color().as_index()][piece.kind_index()]
+                [last_move.end_index as usize],
+        )
+    }
+}
+
+impl Default for MoveOrdering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gives out the legal moves in the order that they are most likely to be best: the move from
+/// the transposition table, captures that don't lose material by most valuable victim then
+/// least valuable attacker (MVV-LVA), killers, the counter-move, the other quiet moves by their
+/// history and then the captures that lose material.
+pub(crate) struct MovePicker {
+    stage: Stage,
+    table_move: Option<Move>,
+    killers: [Option<Move>; 2],
+    counter_move: Option<Move>,
+    /// Captures and queen promotions with their score
+    captures: Vec<(Move, i32)>,
+    /// Captures that lose material in the exchange (by [Board::see]) which are left until last
+    bad_captures: Vec<Move>,
+    /// Leave out the captures that lose material instead of giving them last
+    skip_bad_captures: bool,
+    /// Quiet moves with their history score once the stage to score them is reached
+    quiets: Vec<(Move, i32)>,
+}
+
+impl MovePicker {
+    /// Creates a picker over the legal moves. The table move is only used if it is one of them.
+    pub fn new(
+        board: &Board,
+        moves: Vec<Move>,
+        table_move: Option<Move>,
+        ordering: &MoveOrdering,
+        ply: u32,
+    ) -> Self {
+        let mut picker = Self::from_moves(board, moves, table_move);
+        picker.killers = ordering.killers[ply as usize];
+        picker.counter_move = board.moves_made().last().and_then(|last_move| {
+            let piece = board.piece_at(last_move.end_index)?;
+            ordering.counter_moves[piece.get_color().as_index()][piece.kind_index()]
+                [last_move.end_index as usize]
+        });
+        picker
+    }
+
+    /// Creates a picker for the quiescence search which doesn't have killers or counter-moves.
+    /// Captures that lose material are left out unless the side to move is in check.
+    pub fn new_quiescence(board: &Board, moves: Vec<Move>) -> Self {
+        let mut picker = Self::from_moves(board, moves, None);
+        picker.skip_bad_captures = !board.in_check();
+        picker
+    }
+
+    fn from_moves(board: &Board, moves: Vec<Move>, table_move: Option<Move>) -> Self {
+        let mut picker = Self {
+            stage: Stage::TableMove,
+            table_move: None,
+            killers: [None; 2],
+            counter_move: None,
+            captures: Vec::new(),
+            bad_captures: Vec::new(),
+            skip_bad_captures: false,
+            quiets: Vec::with_capacity(moves.len()),
+        };
+
+        for legal_move in moves {
+            if Some(legal_move) == table_move {
+                picker.table_move = Some(legal_move);
+            } else if is_tactical(board, legal_move) {
+                picker
+                    .captures
+                    .push((legal_move, mvv_lva(board, legal_move)));
+            } else {
+                picker.quiets.push((legal_move, 0));
+            }
+        }
+
+        picker
+    }
+
+    /// Gets the next move to try or none when all the moves have been given
+    pub fn next(&mut self, board: &Board, ordering: &MoveOrdering) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::TableMove => {
+                    self.stage = Stage::Captures;
+                    if self.table_move.is_some() {
+                        return self.table_move;
+                    }
+                }
+                Stage::Captures => match take_best(&mut self.captures) {
+                    Some(capture) if board.see_ge(capture, 0) => return Some(capture),
+                    Some(_) if self.skip_bad_captures => (),
+                    Some(capture) => self.bad_captures.push(capture),
+                    None => self.stage = Stage::Killers,
+                },
+                Stage::Killers => {
+                    // a killer is only used if it's a legal quiet move here
+                    while let Some(killer) = self.killers.iter_mut().find_map(Option::take) {
+                        if let Some(killer) = take_move(&mut self.quiets, killer) {
+                            return Some(killer);
+                        }
+                    }
+                    self.stage = Stage::CounterMove;
+                }
+                Stage::CounterMove => {
+                    self.stage = Stage::ScoreQuiets;
+                    let counter_move = self
+                        .counter_move
+                        .and_then(|counter_move| take_move(&mut self.quiets, counter_move));
+                    if counter_move.is_some() {
+                        return counter_move;
+                    }
+                }
+                Stage::ScoreQuiets => {
+                    for (quiet_move, score) in &mut self.quiets {
+                        *score = ordering.history(board, *quiet_move);
+                    }
+                    self.stage = Stage::Quiets;
+                }
+                Stage::Quiets => match take_best(&mut self.quiets) {
+                    Some(quiet_move) => return Some(quiet_move),
+                    None => self.stage = Stage::BadCaptures,
+                },
+                Stage::BadCaptures => {
+                    // they were added best first
+                    if !self.bad_captures.is_empty() {
+                        return Some(self.bad_captures.remove(0));
+                    }
+                    self.stage = Stage::Done;
+                }
+                Stage::Done => return None,
+            }
+        }
+    }
+}
+
+/// Removes the move with the highest score. Only the moves that are needed get sorted since
+/// most nodes cut off after the first few.
+fn take_best(moves: &mut Vec<(Move, i32)>) -> Option<Move> {
+    let best_index = (0..moves.len()).max_by_key(|&index| moves[index].1)?;
+    Some(moves.swap_remove(best_index).0)
+}
+
+fn take_move(moves: &mut Vec<(Move, i32)>, test_move: Move) -> Option<Move> {
+    let index = moves
+        .iter()
+        .position(|&(legal_move, _)| legal_move == test_move)?;
+    Some(moves.swap_remove(index).0)
+}
+
+/// Scores captures so taking the most valuable piece with the least valuable one comes first
+fn mvv_lva(board: &Board, capture: Move) -> i32 {
+    let attacker = board
+        .piece_at(capture.start_index)
+        .map_or(0, |piece| PIECE_VALUES[piece.kind_index()]);
+    material_gain(board, capture) * 10 - attacker
+}
+
+/// Checks if the move is a capture or a queen promotion which can change the material by a lot.
+/// Promoting to other pieces is almost never better than a queen so it is treated as quiet.
+pub(crate) fn is_tactical(board: &Board, test_move: Move) -> bool {
+    board.move_is_capture(test_move)
+        || matches!(test_move.flag, MoveFlag::Promote(piece) if piece.kind_index() == Piece::QUEEN)
+}
+
+/// Gets the material the move wins straight away without looking at recaptures
+pub(crate) fn material_gain(board: &Board, test_move: Move) -> i32 {
+    let captured = match test_move.flag {
+        MoveFlag::EnPassantCapture => PIECE_VALUES[Piece::PAWN],
+        _ => board
+            .piece_at(test_move.end_index)
+            .map_or(0, |piece| PIECE_VALUES[piece.kind_index()]),
+    };
+    let promoted = match test_move.flag {
+        MoveFlag::Promote(piece) => PIECE_VALUES[piece.kind_index()] - PIECE_VALUES[Piece::PAWN],
+        _ => 0,
+    };
+    captured + promoted
+}
@@ -0,0 +1,267 @@
+use crate::bitboard::{self, Bitboard};
+use crate::{Board, Color, Move, MoveFlag, Piece, Side};
+
+impl Board {
+    /// Adds all the legal moves for the side to move. Pins and checks are worked out up front so
+    /// no move has to be made to test if it leaves the king in check.
+    pub(crate) fn add_legal_moves(&self, moves: &mut Vec<Move>) {
+        self.add_legal_moves_from(moves, Bitboard::FULL);
+    }
+
+    /// Adds the legal moves of the side to move's pieces that start on one of the squares
+    pub(crate) fn add_legal_moves_from(&self, moves: &mut Vec<Move>, start_squares: Bitboard) {
+        let color = self.turn;
+        let own = self.colors[color.as_index()];
+        let pieces = own & start_squares;
+        let occupancy = self.occupancy();
+
+        // without a king there is nothing to keep safe
+        let Some(king_index) = self.find_king(color) else {
+            self.add_piece_moves(moves, pieces, Bitboard::FULL, Bitboard::EMPTY, None);
+            return;
+        };
+        let moves_king = pieces.contains(king_index);
+
+        // the king can't stay on a square a slider attacks through where the king was
+        if moves_king {
+            let without_king = occupancy ^ Bitboard::from_index(king_index);
+            for end_index in bitboard::king_attacks(king_index) & !own {
+                if self
+                    .attackers_with_occupancy(end_index, color.opposite(), without_king)
+                    .is_empty()
+                {
+                    moves.push(Move::new(king_index, end_index));
+                }
+            }
+        }
+
+        let checkers = self.attackers_with_occupancy(king_index, color.opposite(), occupancy);
+        if checkers.has_more_than_one() {
+            // only the king can get out of double check
+            return;
+        }
+
+        // squares that block or capture the checking piece
+        let check_mask = match checkers.lsb() {
+            Some(checker_index) => bitboard::between(king_index, checker_index) | checkers,
+            None => {
+                if moves_king {
+                    self.add_castle_moves(moves, king_index);
+                }
+                Bitboard::FULL
+            }
+        };
+
+        let pieces = pieces & !Bitboard::from_index(king_index);
+        if pieces.is_empty() {
+            return;
+        }
+
+        let pinned = self.pinned(king_index);
+        self.add_piece_moves(moves, pieces, check_mask, pinned, Some(king_index));
+    }
+
+    /// Gets the pieces of the side to move that can't leave the line between their king and an
+    /// opponent slider
+    fn pinned(&self, king_index: u8) -> Bitboard {
+        let color = self.turn;
+        let opponent = self.colors[color.opposite().as_index()];
+        let occupancy = self.occupancy();
+        let queens = self.pieces[Piece::QUEEN];
+
+        // opponent sliders that would attack the king if only opponent pieces were on the board
+        let snipers = ((bitboard::rook_attacks(king_index, opponent)
+            & (self.pieces[Piece::ROOK] | queens))
+            | (bitboard::bishop_attacks(king_index, opponent)
+                & (self.pieces[Piece::BISHOP] | queens)))
+            & opponent;
+
+        let mut pinned = Bitboard::EMPTY;
+        for sniper_index in snipers {
+            let blockers = bitboard::between(king_index, sniper_index) & occupancy;
+            if !blockers.has_more_than_one() {
+                pinned |= blockers & self.colors[color.as_index()];
+            }
+        }
+
+        pinned
+    }
+
+    /// Adds the moves of all the pieces other than the king. Moves must end on the check mask and
+    /// pinned pieces must stay on the line through their king.
+    fn add_piece_moves(
+        &self,
+        moves: &mut Vec<Move>,
+        pieces: Bitboard,
+        check_mask: Bitboard,
+        pinned: Bitboard,
+        king_index: Option<u8>,
+    ) {
+        let own = self.colors[self.turn.as_index()];
+        let occupancy = self.occupancy();
+        let targets = !own & check_mask;
+
+        let pin_mask = |start_index: u8| match king_index {
+            Some(king_index) if pinned.contains(start_index) => {
+                bitboard::line(king_index, start_index)
+            }
+            _ => Bitboard::FULL,
+        };
+
+        macro_rules! add_moves {
+            ($start_index: expr, $end_indices: expr) => {
+                for end_index in $end_indices {
+                    moves.push(Move::new($start_index, end_index));
+                }
+            };
+        }
+
+        // a pinned knight can never stay on the pin line
+        for start_index in self.pieces[Piece::KNIGHT] & pieces & !pinned {
+            add_moves!(start_index, bitboard::knight_attacks(start_index) & targets);
+        }
+
+        let queens = self.pieces[Piece::QUEEN];
+        for start_index in (self.pieces[Piece::BISHOP] | queens) & pieces {
+            let attacks = bitboard::bishop_attacks(start_index, occupancy);
+            add_moves!(start_index, attacks & targets & pin_mask(start_index));
+        }
+
+        for start_index in (self.pieces[Piece::ROOK] | queens) & pieces {
+            let attacks = bitboard::rook_attacks(start_index, occupancy);
+            add_moves!(start_index, attacks & targets & pin_mask(start_index));
+        }
+
+        // kings only get here when there is no king to keep safe
+        if king_index.is_none() {
+            for start_index in self.pieces[Piece::KING] & pieces {
+                add_moves!(start_index, bitboard::king_attacks(start_index) & !own);
+            }
+        }
+
+        for start_index in self.pieces[Piece::PAWN] & pieces {
+            self.add_pawn_moves(
+                moves,
+                start_index,
+                check_mask & pin_mask(start_index),
+                king_index,
+            );
+        }
+    }
+
+    fn add_pawn_moves(
+        &self,
+        moves: &mut Vec<Move>,
+        start_index: u8,
+        allowed: Bitboard,
+        king_index: Option<u8>,
+    ) {
+        let color = self.turn;
+        let occupancy = self.occupancy();
+        let (forward_offset, second_rank, last_rank) = match color {
+            Color::White => (-8, 6, 1),
+            Color::Black => (8, 1, 6),
+        };
+        let about_to_promote = start_index / 8 == last_rank;
+
+        let add_move = |moves: &mut Vec<Move>, end_index: u8| {
+            if about_to_promote {
+                add_promote_moves(moves, start_index, end_index, color);
+            } else {
+                moves.push(Move::new(start_index, end_index));
+            }
+        };
+
+        let push_index = (start_index as i8 + forward_offset) as u8;
+        if !occupancy.contains(push_index) {
+            if allowed.contains(push_index) {
+                add_move(moves, push_index);
+            }
+
+            // on first rank, do double push
+            if start_index / 8 == second_rank {
+                let double_push_index = (push_index as i8 + forward_offset) as u8;
+                if !occupancy.contains(double_push_index) && allowed.contains(double_push_index) {
+                    moves.push(
+                        Move::new(start_index, double_push_index).flag(MoveFlag::PawnDoublePush),
+                    );
+                }
+            }
+        }
+
+        let attacks = bitboard::pawn_attacks(start_index, color);
+        let opponent = self.colors[color.opposite().as_index()];
+        for end_index in attacks & opponent & allowed {
+            add_move(moves, end_index);
+        }
+
+        if let Some(en_passant_square) = self.en_passant_square {
+            if attacks.contains(en_passant_square) {
+                let captured_index = (en_passant_square as i8 - forward_offset) as u8;
+
+                // both pawns leave the rank at once so the simplest check is to look for
+                // attackers on the board as it would be after the capture
+                let occupancy_after = occupancy
+                    ^ Bitboard::from_index(start_index)
+                    ^ Bitboard::from_index(captured_index)
+                    ^ Bitboard::from_index(en_passant_square);
+                let is_king_safe = king_index.is_none_or(|king_index| {
+                    self.attackers_with_occupancy(king_index, color.opposite(), occupancy_after)
+                        .is_empty()
+                });
+
+                if is_king_safe {
+                    moves.push(
+                        Move::new(start_index, en_passant_square).flag(MoveFlag::EnPassantCapture),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Adds castle moves for the king when it isn't in check
+    fn add_castle_moves(&self, moves: &mut Vec<Move>, king_index: u8) {
+        let color = self.turn;
+        let first_rank_index: u8 = match color {
+            Color::White => 56,
+            Color::Black => 0,
+        };
+        if king_index != first_rank_index + 4 {
+            return;
+        }
+
+        for (side, rook_file, end_file) in [(Side::Queen, 0, 2), (Side::King, 7, 6)] {
+            let rook_index = first_rank_index + rook_file;
+            let end_index = first_rank_index + end_file;
+            if self.castle_rights[color.as_index()][side.as_index()]
+                && self.piece_at(rook_index) == Some(Piece::Rook(color))
+                && self.can_castle(king_index, rook_index, end_index)
+            {
+                moves.push(Move::new(king_index, end_index).flag(MoveFlag::Castle(side)));
+            }
+        }
+    }
+
+    /// Checks that the squares between the king and rook are empty and that the king is not
+    /// castling through or into check.
+    fn can_castle(&self, king_index: u8, rook_index: u8, end_index: u8) -> bool {
+        let occupancy = self.occupancy();
+        if !(bitboard::between(king_index, rook_index) & occupancy).is_empty() {
+            return false;
+        }
+
+        let king_path = bitboard::between(king_index, end_index) | Bitboard::from_index(end_index);
+        king_path.into_iter().all(|index| {
+            self.attackers_with_occupancy(index, self.turn.opposite(), occupancy)
+                .is_empty()
+        })
+    }
+}
+
+fn add_promote_moves(moves: &mut Vec<Move>, start_index: u8, end_index: u8, color: Color) {
+    let base_move = Move::new(start_index, end_index);
+    moves.push(base_move.flag(MoveFlag::Promote(Piece::Queen(color))));
+    moves.push(base_move.flag(MoveFlag::Promote(Piece::Rook(color))));
+    moves.push(base_move.flag(MoveFlag::Promote(Piece::Bishop(color))));
+    moves.push(base_move.flag(MoveFlag::Promote(Piece::Knight(color))));
+}
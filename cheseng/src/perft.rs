@@ -0,0 +1,50 @@
+use crate::{Board, Move};
+
+/// Counts the number of positions reachable from the board in exactly depth moves. Comparing the
+/// count with known results is the standard way of checking move generation.
+pub fn perft(board: &Board, depth: u32) -> u64 {
+    count_nodes(&mut board.clone(), depth)
+}
+
+/// Counts the positions reachable after each legal move which narrows down the moves where the
+/// count differs from another move generator. Empty when depth is 0.
+pub fn perft_divide(board: &Board, depth: u32) -> Vec<(Move, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    let mut board = board.clone();
+    board
+        .get_all_legal_moves()
+        .into_iter()
+        .map(|legal_move| {
+            board.make_move(legal_move);
+            let num_nodes = count_nodes(&mut board, depth - 1);
+            board.unmake_move();
+            (legal_move, num_nodes)
+        })
+        .collect()
+}
+
+fn count_nodes(board: &mut Board, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = board.get_all_legal_moves();
+
+    // every legal move leads to exactly one position so there is no need to make them
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    moves
+        .into_iter()
+        .map(|legal_move| {
+            board.make_move(legal_move);
+            let num_nodes = count_nodes(board, depth - 1);
+            board.unmake_move();
+            num_nodes
+        })
+        .sum()
+}
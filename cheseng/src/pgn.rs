@@ -0,0 +1,671 @@
+use crate::error::{PgnError, PgnErrorKind};
+use crate::game::SEVEN_TAG_ROSTER;
+use crate::{Board, Error, Game, Move};
+use std::time::Duration;
+
+/// A game read from Portable Game Notation
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnGame {
+    /// Tag pairs in the order they were written (eg. ("Event", "Casual Game"))
+    pub tags: Vec<(String, String)>,
+    /// The moves of the main line with any variations branching off them
+    pub moves: Vec<PgnNode>,
+    pub result: GameResult,
+}
+
+/// A move in a game along with its annotations
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnNode {
+    pub chess_move: Move,
+    /// The move in Standard Algebraic Notation as generated by [Board::move_to_san]
+    pub san: String,
+    /// Numeric Annotation Glyphs ($1 or ! is 1, $2 or ? is 2, etc.)
+    pub nags: Vec<u8>,
+    /// Comments written before the move. Only used at the start of the game or a variation.
+    pub comments_before: Vec<String>,
+    /// Comments written after the move without the [%eval] and [%clk] commands
+    pub comments_after: Vec<String>,
+    /// Engine evaluation of the position after the move from a [%eval] command
+    pub eval: Option<Evaluation>,
+    /// Time left on the clock of the side that moved from a [%clk] command
+    pub clock: Option<Duration>,
+    /// Lines of moves that could have been played instead of this move
+    pub variations: Vec<Vec<PgnNode>>,
+}
+
+/// An engine evaluation from white's point of view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Evaluation {
+    Centipawns(i32),
+    /// Number of moves until mate with negative numbers for black mating
+    Mate(i32),
+}
+
+impl std::fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Centipawns(centipawns) => {
+                let sign = if *centipawns < 0 { "-" } else { "" };
+                let centipawns = centipawns.unsigned_abs();
+                write!(f, "{}{}.{:02}", sign, centipawns / 100, centipawns % 100)
+            }
+            Self::Mate(moves) => write!(f, "#{}", moves),
+        }
+    }
+}
+
+impl std::str::FromStr for Evaluation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('#') {
+            Some(moves) => moves.parse().map(Self::Mate).or(Err(())),
+            None => {
+                let pawns = s.parse::<f64>().or(Err(()))?;
+                if !pawns.is_finite() {
+                    return Err(());
+                }
+                Ok(Self::Centipawns((pawns * 100.0).round() as i32))
+            }
+        }
+    }
+}
+
+impl PgnNode {
+    pub fn new(chess_move: Move, san: String) -> Self {
+        Self {
+            chess_move,
+            san,
+            nags: Vec::new(),
+            comments_before: Vec::new(),
+            comments_after: Vec::new(),
+            eval: None,
+            clock: None,
+            variations: Vec::new(),
+        }
+    }
+
+    /// Adds a comment after the move taking out any [%eval] and [%clk] commands
+    fn add_comment_after(&mut self, comment: &str) {
+        let mut text = String::new();
+        let mut rest = comment;
+        while let Some(command_start) = rest.find("[%") {
+            let Some(command_len) = rest[command_start..].find(']') else {
+                break;
+            };
+
+            let command = &rest[command_start + 2..command_start + command_len];
+            let (name, value) = command.split_once(' ').unwrap_or((command, ""));
+            let is_known = match name {
+                "eval" => value
+                    .trim()
+                    .parse()
+                    .map(|eval| self.eval = Some(eval))
+                    .is_ok(),
+                "clk" => parse_clock(value.trim())
+                    .map(|clock| self.clock = Some(clock))
+                    .is_some(),
+                _ => false,
+            };
+
+            // unknown commands are kept in the comment
+            let end = command_start + command_len + 1;
+            text.push_str(&rest[..if is_known { command_start } else { end }]);
+            rest = &rest[end..];
+        }
+        text.push_str(rest);
+
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            self.comments_after.push(text);
+        }
+    }
+}
+
+/// Parses a clock time written as h:mm:ss with optional fractions of a second
+fn parse_clock(clock: &str) -> Option<Duration> {
+    let mut parts = clock.split(':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || minutes >= 60 || !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+
+    Some(Duration::from_secs(hours * 3600 + minutes * 60) + Duration::from_secs_f64(seconds))
+}
+
+/// Writes a clock time as h:mm:ss with fractions of a second only if there are any
+fn format_clock(clock: Duration) -> String {
+    let seconds = clock.as_secs();
+    let mut formatted = format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+
+    let millis = clock.subsec_millis();
+    if millis != 0 {
+        let fraction = format!("{:03}", millis);
+        formatted.push('.');
+        formatted.push_str(fraction.trim_end_matches('0'));
+    }
+
+    formatted
+}
+
+/// How a game ended as written at the end of the movetext
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    /// The game is still going or the result isn't known (*)
+    Unknown,
+}
+
+impl std::fmt::Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::WhiteWins => "1-0",
+                Self::BlackWins => "0-1",
+                Self::Draw => "1/2-1/2",
+                Self::Unknown => "*",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for GameResult {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1-0" => Ok(Self::WhiteWins),
+            "0-1" => Ok(Self::BlackWins),
+            "1/2-1/2" => Ok(Self::Draw),
+            "*" => Ok(Self::Unknown),
+            _ => Err(()),
+        }
+    }
+}
+
+impl PgnGame {
+    /// Gets the value of the first tag with the name
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Gets the board before any moves are made which is the FEN tag if there is one
+    pub fn starting_board(&self) -> Result<Board, Error> {
+        match self.tag("FEN") {
+            Some(fen) => Board::from_fen(fen),
+            None => Ok(Board::default()),
+        }
+    }
+
+    /// Creates a PGN game from the moves made on the board
+    pub fn from_board(board: &Board) -> Self {
+        Game::from_board(board.clone()).to_pgn()
+    }
+
+    /// Gets the board after all the moves of the main line are made
+    pub fn board(&self) -> Result<Board, Error> {
+        Ok(Game::from_pgn(self)?.board().clone())
+    }
+}
+
+/// Writes the games in PGN separated by blank lines
+pub fn write_games(games: &[PgnGame]) -> String {
+    games
+        .iter()
+        .map(|game| game.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Writes the game in PGN export format with the seven tag roster first (using "?" for missing
+/// tags) and the movetext wrapped to 80 columns
+impl std::fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (name, default_value) in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => self.result.to_string(),
+                _ => self.tag(name).unwrap_or(default_value).to_owned(),
+            };
+            write_tag(f, name, &value)?;
+        }
+
+        for (name, value) in &self.tags {
+            if !SEVEN_TAG_ROSTER
+                .iter()
+                .any(|&(roster_name, _)| roster_name == name)
+            {
+                write_tag(f, name, value)?;
+            }
+        }
+
+        // games from a FEN can start on any move and with black to move
+        let start_ply = self.starting_board().map_or(0, |board| {
+            (board.fullmove_number as usize - 1) * 2 + board.turn.as_index()
+        });
+
+        let mut movetext = Vec::new();
+        write_line(&mut movetext, &self.moves, start_ply);
+        movetext.push(self.result.to_string());
+
+        // break lines between words so they aren't longer than 80 chars
+        writeln!(f)?;
+        let mut line_len = 0;
+        for word in movetext.iter().flat_map(|token| token.split_whitespace()) {
+            if line_len > 0 && line_len + 1 + word.chars().count() > 80 {
+                writeln!(f)?;
+                line_len = 0;
+            }
+            if line_len > 0 {
+                write!(f, " ")?;
+                line_len += 1;
+            }
+            write!(f, "{}", word)?;
+            line_len += word.chars().count();
+        }
+
+        writeln!(f)
+    }
+}
+
+fn write_tag(f: &mut std::fmt::Formatter, name: &str, value: &str) -> std::fmt::Result {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(f, "[{} \"{}\"]", name, value)
+}
+
+/// Adds the tokens for a line of moves where the first move is at the ply (halfmove from the
+/// start of the game)
+fn write_line(movetext: &mut Vec<String>, nodes: &[PgnNode], start_ply: usize) {
+    // black's moves only need a number at the start or after something that isn't a move
+    let mut needs_number = true;
+    for (ply, node) in (start_ply..).zip(nodes) {
+        for comment in &node.comments_before {
+            movetext.push(format_comment(comment));
+            needs_number = true;
+        }
+
+        let move_number = ply / 2 + 1;
+        if ply % 2 == 0 {
+            movetext.push(format!("{}.", move_number));
+        } else if needs_number {
+            movetext.push(format!("{}...", move_number));
+        }
+        movetext.push(node.san.clone());
+        needs_number = false;
+
+        for nag in &node.nags {
+            movetext.push(format!("${}", nag));
+        }
+
+        let mut commands = Vec::new();
+        if let Some(eval) = node.eval {
+            commands.push(format!("[%eval {}]", eval));
+        }
+        if let Some(clock) = node.clock {
+            commands.push(format!("[%clk {}]", format_clock(clock)));
+        }
+        if !commands.is_empty() {
+            movetext.push(format_comment(&commands.join(" ")));
+            needs_number = true;
+        }
+
+        for comment in &node.comments_after {
+            movetext.push(format_comment(comment));
+            needs_number = true;
+        }
+
+        for variation in &node.variations {
+            let mut variation_text = Vec::new();
+            write_line(&mut variation_text, variation, ply);
+            movetext.push(format!("({})", variation_text.join(" ")));
+            needs_number = true;
+        }
+    }
+}
+
+/// Puts the comment in braces taking out any closing braces since they can't be escaped
+fn format_comment(comment: &str) -> String {
+    format!("{{{}}}", comment.replace('}', ""))
+}
+
+/// Reads every game in the PGN. Moves are checked to be legal as they are read.
+pub fn read_games(pgn: &str) -> Result<Vec<PgnGame>, Error> {
+    let mut reader = Reader::new(pgn);
+    let mut games = Vec::new();
+    while let Some(game) = reader.read_game()? {
+        games.push(game);
+    }
+
+    Ok(games)
+}
+
+/// Suffix annotations that are short for the first six NAGs
+const SUFFIX_ANNOTATIONS: [&str; 6] = ["!", "?", "!!", "??", "!?", "?!"];
+
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(pgn: &'a str) -> Self {
+        Self {
+            chars: pgn.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let char = self.chars.next()?;
+        if char == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(char)
+    }
+
+    fn error(&self, (line, column): (usize, usize), kind: PgnErrorKind) -> PgnError {
+        PgnError { line, column, kind }
+    }
+
+    /// Skips whitespace and lines starting with % which are escaped from PGN
+    fn skip_whitespace(&mut self) {
+        while let Some(char) = self.peek() {
+            if char == '%' && self.column == 1 {
+                while self.next_char().is_some_and(|char| char != '\n') {}
+            } else if char.is_whitespace() {
+                self.next_char();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Reads chars while they could be part of a move, move number, result or annotation
+    fn read_symbol(&mut self) -> String {
+        let mut symbol = String::new();
+        while let Some(char) = self
+            .peek()
+            .filter(|char| char.is_alphanumeric() || "_+#=:-/.!?".contains(*char))
+        {
+            symbol.push(char);
+            self.next_char();
+        }
+
+        symbol
+    }
+
+    fn read_game(&mut self) -> Result<Option<PgnGame>, PgnError> {
+        self.skip_whitespace();
+        if self.peek().is_none() {
+            return Ok(None);
+        }
+
+        let mut tags = Vec::new();
+        let mut fen_position = None;
+        while self.peek() == Some('[') {
+            let position = (self.line, self.column);
+            let tag = self.read_tag()?;
+            if tag.0 == "FEN" && fen_position.is_none() {
+                fen_position = Some(position);
+            }
+            tags.push(tag);
+            self.skip_whitespace();
+        }
+
+        let mut game = PgnGame {
+            tags,
+            moves: Vec::new(),
+            result: GameResult::Unknown,
+        };
+
+        let mut board = match game.starting_board() {
+            Ok(board) => board,
+            Err(err) => {
+                let position = fen_position.unwrap_or((self.line, self.column));
+                let kind = match err {
+                    Error::InvalidFEN(fen_error) => PgnErrorKind::InvalidFen(fen_error),
+                    err => PgnErrorKind::InvalidGame(format!("{:?}", err)),
+                };
+                return Err(self.error(position, kind));
+            }
+        };
+
+        let (moves, result) = self.read_line(&mut board, false)?;
+        game.moves = moves;
+
+        // the result tag is used if the movetext stops without one
+        game.result = result
+            .or_else(|| game.tag("Result")?.parse().ok())
+            .unwrap_or(GameResult::Unknown);
+
+        Ok(Some(game))
+    }
+
+    /// Reads a tag pair like [Event "Casual Game"]
+    fn read_tag(&mut self) -> Result<(String, String), PgnError> {
+        let position = (self.line, self.column);
+        let invalid_tag = |reader: &Self| reader.error(position, PgnErrorKind::InvalidTag);
+
+        // skip [
+        self.next_char();
+        self.skip_whitespace();
+
+        let mut name = String::new();
+        while let Some(char) = self
+            .peek()
+            .filter(|char| char.is_alphanumeric() || *char == '_')
+        {
+            name.push(char);
+            self.next_char();
+        }
+
+        self.skip_whitespace();
+        if name.is_empty() || self.next_char() != Some('"') {
+            return Err(invalid_tag(self));
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.next_char() {
+                Some('"') => break,
+                Some('\\') => value.push(self.next_char().ok_or(invalid_tag(self))?),
+                Some('\n') | None => return Err(invalid_tag(self)),
+                Some(char) => value.push(char),
+            }
+        }
+
+        self.skip_whitespace();
+        if self.next_char() != Some(']') {
+            return Err(invalid_tag(self));
+        }
+
+        Ok((name, value))
+    }
+
+    /// Reads moves until the end of the game or variation. The moves are made on the board and
+    /// for a variation they are unmade again before returning.
+    fn read_line(
+        &mut self,
+        board: &mut Board,
+        is_variation: bool,
+    ) -> Result<(Vec<PgnNode>, Option<GameResult>), PgnError> {
+        let mut nodes: Vec<PgnNode> = Vec::new();
+        let mut comments_before = Vec::new();
+        let result = loop {
+            self.skip_whitespace();
+            let position = (self.line, self.column);
+            let Some(char) = self.peek() else {
+                if is_variation {
+                    return Err(self.error(position, PgnErrorKind::UnexpectedEnd));
+                }
+                break None;
+            };
+
+            match char {
+                '{' | ';' => {
+                    let comment = self.read_comment()?;
+                    match nodes.last_mut() {
+                        Some(node) => node.add_comment_after(&comment),
+                        None => comments_before.push(comment),
+                    }
+                }
+                '$' => {
+                    self.next_char();
+                    let nag = self
+                        .read_symbol()
+                        .parse()
+                        .map_err(|_| self.error(position, PgnErrorKind::InvalidNag))?;
+                    nodes
+                        .last_mut()
+                        .ok_or(self.error(position, PgnErrorKind::NagWithoutMove))?
+                        .nags
+                        .push(nag);
+                }
+                '(' => {
+                    self.next_char();
+                    let node = nodes
+                        .last_mut()
+                        .ok_or(self.error(position, PgnErrorKind::VariationWithoutMove))?;
+
+                    // a variation replaces the move before it
+                    board.unmake_move();
+                    let (variation, _) = self.read_line(board, true)?;
+                    board.make_move(node.chess_move);
+
+                    if variation.is_empty() {
+                        return Err(self.error(position, PgnErrorKind::EmptyVariation));
+                    }
+                    node.variations.push(variation);
+                }
+                ')' if is_variation => {
+                    self.next_char();
+                    for _ in &nodes {
+                        board.unmake_move();
+                    }
+                    break None;
+                }
+                // a new game starting without the last one being terminated
+                '[' if !is_variation => break None,
+                '*' => {
+                    self.next_char();
+                    if is_variation {
+                        return Err(self.error(position, PgnErrorKind::UnexpectedChar('*')));
+                    }
+                    break Some(GameResult::Unknown);
+                }
+                _ => {
+                    let symbol = self.read_symbol();
+                    if symbol.is_empty() {
+                        return Err(self.error(position, PgnErrorKind::UnexpectedChar(char)));
+                    }
+
+                    if let Ok(result) = symbol.parse::<GameResult>() {
+                        if is_variation {
+                            return Err(self.error(position, PgnErrorKind::UnexpectedResult));
+                        }
+                        break Some(result);
+                    }
+
+                    // move numbers (1. or 1...) can be right next to the move (1.e4)
+                    let symbol = match symbol.find('.') {
+                        Some(dot_index)
+                            if symbol[..dot_index].chars().all(|c| c.is_ascii_digit()) =>
+                        {
+                            symbol[dot_index..].trim_start_matches('.')
+                        }
+                        _ => &symbol,
+                    };
+                    if symbol.is_empty() {
+                        continue;
+                    }
+
+                    // suffix annotations can be attached to the move or on their own
+                    let san = symbol.trim_end_matches(['!', '?']);
+                    let suffix = &symbol[san.len()..];
+                    let suffix_nag = match suffix {
+                        "" => None,
+                        _ => Some(
+                            SUFFIX_ANNOTATIONS
+                                .iter()
+                                .position(|&annotation| annotation == suffix)
+                                .map(|index| index as u8 + 1)
+                                .ok_or(self.error(position, PgnErrorKind::InvalidNag))?,
+                        ),
+                    };
+
+                    if san.is_empty() {
+                        let nag = suffix_nag.expect("symbol isn't empty");
+                        nodes
+                            .last_mut()
+                            .ok_or(self.error(position, PgnErrorKind::NagWithoutMove))?
+                            .nags
+                            .push(nag);
+                        continue;
+                    }
+
+                    let chess_move = board.parse_san(san).map_err(|err| {
+                        let kind = match err {
+                            Error::InvalidSAN(san_error) => {
+                                PgnErrorKind::InvalidMove(san.to_owned(), san_error)
+                            }
+                            err => PgnErrorKind::InvalidGame(format!("{:?}", err)),
+                        };
+                        self.error(position, kind)
+                    })?;
+
+                    let mut node = PgnNode::new(chess_move, board.move_to_san(chess_move));
+                    node.nags.extend(suffix_nag);
+                    node.comments_before = std::mem::take(&mut comments_before);
+                    nodes.push(node);
+                    board.make_move(chess_move);
+                }
+            }
+        };
+
+        Ok((nodes, result))
+    }
+
+    /// Reads a {} comment or a ; comment that goes to the end of the line
+    fn read_comment(&mut self) -> Result<String, PgnError> {
+        let position = (self.line, self.column);
+        let end_char = match self.next_char() {
+            Some('{') => '}',
+            _ => '\n',
+        };
+
+        let mut comment = String::new();
+        loop {
+            match self.next_char() {
+                Some(char) if char == end_char => break,
+                Some(char) => comment.push(char),
+                None if end_char == '\n' => break,
+                None => return Err(self.error(position, PgnErrorKind::UnexpectedEnd)),
+            }
+        }
+
+        Ok(comment.trim().to_owned())
+    }
+}
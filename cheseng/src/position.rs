@@ -66,12 +66,24 @@ impl std::str::FromStr for Position {
 
         let rank_char = chars.next().ok_or(Error::InvalidPosition(None))?;
         let rank = 8 - rank_char
-            .to_digit(9)
+            .to_digit(10)
+            .filter(|rank| (1..=8).contains(rank))
             .ok_or(Error::InvalidPosition(Some(rank_char)))?;
+
+        if let Some(char) = chars.next() {
+            Err(Error::InvalidPosition(Some(char)))?;
+        }
+
         Ok(Self::new(file, rank as u8))
     }
 }
 
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", (b'a' + self.file) as char, 8 - self.rank)
+    }
+}
+
 /// Alias to Position::new for short position creation
 pub const fn pos(file: u8, rank: u8) -> Position {
     Position::new(file, rank)
@@ -0,0 +1,195 @@
+use crate::error::SanError;
+use crate::{Board, Color, Error, Move, MoveFlag, Piece, Position, Side};
+
+impl Board {
+    /// Writes a legal move in Standard Algebraic Notation (eg. "Nf3", "exd5", "e8=Q+", "O-O").
+    /// The move is expected to come from [Board::get_all_legal_moves].
+    pub fn move_to_san(&self, legal_move: Move) -> String {
+        let mut san = match legal_move.flag {
+            MoveFlag::Castle(Side::King) => "O-O".to_owned(),
+            MoveFlag::Castle(Side::Queen) => "O-O-O".to_owned(),
+            _ => self.move_to_san_without_suffix(legal_move),
+        };
+
+        let mut board = self.clone();
+        board.make_move(legal_move);
+        if board.in_check() {
+            san.push(if board.get_all_legal_moves().is_empty() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+
+        san
+    }
+
+    fn move_to_san_without_suffix(&self, legal_move: Move) -> String {
+        let mut san = String::new();
+        let start_pos = Position::from_index(legal_move.start_index);
+        let start_square = start_pos.to_string();
+        let is_capture = self.move_is_capture(legal_move);
+
+        match self.piece_at(legal_move.start_index) {
+            Some(Piece::Pawn(_)) | None => {
+                // pawn captures always say which file the pawn came from
+                if is_capture {
+                    san.push_str(&start_square[..1]);
+                }
+            }
+            Some(piece) => {
+                san.push(piece.get_fen_char().to_ascii_uppercase());
+
+                // other pieces of the same type that can move to the same square
+                let others: Vec<Position> = self
+                    .get_all_legal_moves()
+                    .into_iter()
+                    .filter(|other_move| {
+                        other_move.end_index == legal_move.end_index
+                            && other_move.start_index != legal_move.start_index
+                            && self.piece_at(other_move.start_index) == Some(piece)
+                    })
+                    .map(|other_move| Position::from_index(other_move.start_index))
+                    .collect();
+
+                if !others.is_empty() {
+                    if others.iter().all(|other| other.file != start_pos.file) {
+                        san.push_str(&start_square[..1]);
+                    } else if others.iter().all(|other| other.rank != start_pos.rank) {
+                        san.push_str(&start_square[1..]);
+                    } else {
+                        san.push_str(&start_square);
+                    }
+                }
+            }
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+        san.push_str(&Position::from_index(legal_move.end_index).to_string());
+
+        if let MoveFlag::Promote(piece) = legal_move.flag {
+            san.push('=');
+            san.push(piece.get_fen_char().to_ascii_uppercase());
+        }
+
+        san
+    }
+
+    /// Finds the legal move written in Standard Algebraic Notation. Check, mate and annotation
+    /// suffixes (+, #, !, ?) are ignored and castling can be written with O's or zeros.
+    pub fn parse_san(&self, san: &str) -> Result<Move, Error> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+        let castle_side = match san {
+            "O-O" | "0-0" => Some(Side::King),
+            "O-O-O" | "0-0-0" => Some(Side::Queen),
+            _ => None,
+        };
+
+        if let Some(side) = castle_side {
+            return self
+                .get_all_legal_moves()
+                .into_iter()
+                .find(|legal_move| match legal_move.flag {
+                    MoveFlag::Castle(move_side) => move_side == side,
+                    _ => false,
+                })
+                .ok_or(Error::InvalidSAN(SanError::IllegalMove));
+        }
+
+        let notation =
+            SanParts::parse(san, self.turn).ok_or(Error::InvalidSAN(SanError::InvalidNotation))?;
+        let mut matching_moves = self.get_all_legal_moves().into_iter().filter(|legal_move| {
+            let start_pos = Position::from_index(legal_move.start_index);
+            let promote_piece = match legal_move.flag {
+                MoveFlag::Promote(piece) => Some(piece),
+                _ => None,
+            };
+
+            legal_move.end_index == notation.end_index
+                && self.piece_at(legal_move.start_index) == Some(notation.piece)
+                && notation
+                    .start_file
+                    .is_none_or(|file| file == start_pos.file)
+                && notation
+                    .start_rank
+                    .is_none_or(|rank| rank == start_pos.rank)
+                && promote_piece == notation.promote_piece
+        });
+
+        let legal_move = matching_moves
+            .next()
+            .ok_or(Error::InvalidSAN(SanError::IllegalMove))?;
+        if matching_moves.next().is_some() {
+            return Err(Error::InvalidSAN(SanError::AmbiguousMove));
+        }
+
+        Ok(legal_move)
+    }
+}
+
+/// The parts of a SAN move other than castling without checking if the move is legal
+struct SanParts {
+    piece: Piece,
+    start_file: Option<u8>,
+    start_rank: Option<u8>,
+    end_index: u8,
+    promote_piece: Option<Piece>,
+}
+
+impl SanParts {
+    fn parse(san: &str, color: Color) -> Option<Self> {
+        // pieces are always uppercase in SAN but uppercase FEN chars are white pieces
+        let piece_from_char = |char: char| match color {
+            Color::White => Piece::from_fen_char(char),
+            Color::Black => Piece::from_fen_char(char.to_ascii_lowercase()),
+        };
+
+        // the promotion can be written as e8=Q or e8Q
+        let (san, promote_piece) = match san.char_indices().last()? {
+            (index, char) if char.is_ascii_uppercase() => {
+                let promote_piece = piece_from_char(char)
+                    .filter(|piece| !matches!(piece, Piece::Pawn(_) | Piece::King(_)))?;
+                (
+                    san[..index].strip_suffix('=').unwrap_or(&san[..index]),
+                    Some(promote_piece),
+                )
+            }
+            _ => (san, None),
+        };
+
+        let (piece, san) = match san.chars().next()? {
+            char if char.is_ascii_uppercase() => (piece_from_char(char)?, &san[1..]),
+            _ => (Piece::Pawn(color), san),
+        };
+
+        // the end square is always the last two chars
+        let end_start = san.len().checked_sub(2)?;
+        if !san.is_char_boundary(end_start) {
+            return None;
+        }
+        let (disambiguation, end_square) = san.split_at(end_start);
+        let end_index = end_square.parse::<Position>().ok()?.as_index().ok()?;
+
+        let disambiguation = disambiguation.strip_suffix('x').unwrap_or(disambiguation);
+        let mut chars = disambiguation.chars().peekable();
+        let start_file = chars
+            .next_if(|char| ('a'..='h').contains(char))
+            .map(|char| char as u8 - b'a');
+        let start_rank = chars
+            .next_if(|char| ('1'..='8').contains(char))
+            .map(|char| b'8' - char as u8);
+        if chars.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            piece,
+            start_file,
+            start_rank,
+            end_index,
+            promote_piece,
+        })
+    }
+}
@@ -0,0 +1,475 @@
+use crate::move_picker::{is_tactical, material_gain, MoveOrdering, MovePicker};
+use crate::time_manager::TimeManager;
+pub use crate::transposition_table::{
+    Bound, ReplacementPolicy, TableEntry, TranspositionTable, DEFAULT_BUCKET_SIZE,
+};
+use crate::{Board, Move};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Score for checkmating on the current move. Mates further away score less by the number of
+/// halfmoves to them so the quickest mate is preferred.
+pub const MATE_SCORE: i32 = 30000;
+/// Score bigger than any score a position can get
+pub const INFINITY: i32 = 32000;
+/// The most halfmoves the search can go from the root
+pub const MAX_PLY: u32 = 128;
+
+/// Size of the transposition table in megabytes when the searcher is created
+pub const DEFAULT_HASH_SIZE_MB: usize = 16;
+
+/// How often the stop flag is checked in nodes
+const STOP_CHECK_INTERVAL: u64 = 1024;
+/// Extra centipawns a capture could gain from where the pieces end up. Captures that can't
+/// bring the score up to alpha even with this are skipped in the quiescence search.
+const DELTA_MARGIN: i32 = 200;
+
+/// A score that can be shown to the user
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Score {
+    Centipawns(i32),
+    /// Mate in the number of moves (not halfmoves) which is negative when getting mated. Mate
+    /// in 0 is when the side to move is already checkmated.
+    Mate(i32),
+}
+
+impl Score {
+    /// Converts a score from the search into centipawns or the moves to mate
+    pub fn from_search_score(score: i32) -> Self {
+        let plies_to_mate = MATE_SCORE - score.abs();
+        if plies_to_mate > MAX_PLY as i32 {
+            Self::Centipawns(score)
+        } else if score > 0 {
+            Self::Mate((plies_to_mate + 1) / 2)
+        } else {
+            Self::Mate(-plies_to_mate / 2)
+        }
+    }
+}
+
+impl std::fmt::Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Centipawns(centipawns) => write!(f, "{:+.2}", *centipawns as f64 / 100.0),
+            Self::Mate(moves) => write!(f, "#{}", moves),
+        }
+    }
+}
+
+/// Checks if the search score is a checkmate for either side
+pub fn is_mate_score(score: i32) -> bool {
+    score.abs() >= MATE_SCORE - MAX_PLY as i32
+}
+
+/// What the search is limited to (usually given by the GUI or the game clock)
+#[derive(Clone, Debug, Default)]
+pub struct Limits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    /// Exact time to search for
+    pub move_time: Option<Duration>,
+    /// Time left on the clock indexed by [crate::Color::as_index]
+    pub time_left: [Option<Duration>; 2],
+    /// Time added after each move indexed by [crate::Color::as_index]
+    pub increment: [Duration; 2],
+    /// Moves until the next time control adds more time
+    pub moves_to_go: Option<u32>,
+    /// Keep searching until stopped
+    pub infinite: bool,
+}
+
+/// Progress of the search after each depth of [Searcher::think]
+#[derive(Clone, Debug)]
+pub struct SearchInfo {
+    pub depth: u32,
+    pub score: Score,
+    /// Nodes searched for all the depths so far
+    pub nodes: u64,
+    pub time: Duration,
+    pub pv: Vec<Move>,
+    /// How full the transposition table is in permille
+    pub hashfull: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    /// The best move or none if there are no legal moves or the search was stopped before a
+    /// move was searched
+    pub best_move: Option<Move>,
+    /// Score from the side to move's point of view in centipawns or [MATE_SCORE] minus the
+    /// halfmoves to mate
+    pub score: i32,
+    /// The moves both sides are expected to play starting with the best move
+    pub pv: Vec<Move>,
+    pub depth: u32,
+    pub nodes: u64,
+    /// The search was stopped before it finished so the result may not be the best
+    pub stopped: bool,
+}
+
+/// Searches positions for the best move. It can be stopped from another thread with the flag
+/// from [Searcher::stop_flag]. The transposition table is kept between searches.
+pub struct Searcher {
+    transposition_table: TranspositionTable,
+    move_ordering: MoveOrdering,
+    stop: Arc<AtomicBool>,
+    stopped: bool,
+    nodes: u64,
+    /// When the search has to stop
+    deadline: Option<Instant>,
+    max_nodes: Option<u64>,
+}
+
+impl Searcher {
+    pub fn new() -> Self {
+        Self {
+            transposition_table: TranspositionTable::new(DEFAULT_HASH_SIZE_MB),
+            move_ordering: MoveOrdering::new(),
+            stop: Arc::new(AtomicBool::new(false)),
+            stopped: false,
+            nodes: 0,
+            deadline: None,
+            max_nodes: None,
+        }
+    }
+
+    /// Gets the flag that stops the search when set. It isn't cleared by the searcher.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    /// Gets the transposition table to resize or clear (eg. for a new game)
+    pub fn transposition_table(&mut self) -> &mut TranspositionTable {
+        &mut self.transposition_table
+    }
+
+    /// Clears everything learnt from earlier searches for a new game
+    pub fn new_game(&mut self) {
+        self.transposition_table.clear();
+        self.move_ordering.clear();
+    }
+
+    /// Searches one halfmove deeper each time until the limits are reached or the search is
+    /// stopped. The info callback is called after each depth and the result is from the deepest
+    /// depth that finished.
+    pub fn think(
+        &mut self,
+        board: &Board,
+        limits: &Limits,
+        mut on_info: impl FnMut(&SearchInfo),
+    ) -> SearchResult {
+        let time_manager = TimeManager::new(limits, board.turn);
+        self.transposition_table.new_search();
+        self.move_ordering.new_search();
+        let max_depth = limits.depth.unwrap_or(MAX_PLY).clamp(1, MAX_PLY);
+        let mut nodes = 0;
+        let mut best_result: Option<SearchResult> = None;
+        let mut best_move_stability = 0;
+
+        // searching deeper can't change the move when there is only one
+        let has_one_move = board.get_all_legal_moves().len() == 1;
+
+        for depth in 1..=max_depth {
+            self.deadline = time_manager.deadline();
+            self.max_nodes = limits
+                .nodes
+                .map(|max_nodes| max_nodes.saturating_sub(nodes));
+            let mut result = self.search_depth(board, depth);
+            nodes += result.nodes;
+            result.nodes = nodes;
+
+            // a depth that didn't finish is only used when there isn't a result yet
+            if result.stopped {
+                best_result.get_or_insert(result).stopped = true;
+                break;
+            }
+
+            on_info(&SearchInfo {
+                depth,
+                score: Score::from_search_score(result.score),
+                nodes,
+                time: time_manager.elapsed(),
+                pv: result.pv.clone(),
+                hashfull: self.transposition_table.hashfull(),
+            });
+
+            let score_drop = match &best_result {
+                Some(last_result) => {
+                    if last_result.best_move == result.best_move {
+                        best_move_stability += 1;
+                    } else {
+                        best_move_stability = 0;
+                    }
+                    last_result.score - result.score
+                }
+                None => 0,
+            };
+
+            // a mate within the depth has been fully searched so a deeper search finds the same
+            let is_proven_mate =
+                is_mate_score(result.score) && MATE_SCORE - result.score.abs() <= depth as i32;
+            let is_over = result.best_move.is_none() || has_one_move || is_proven_mate;
+            best_result = Some(result);
+            if is_over || time_manager.should_stop(best_move_stability, score_drop) {
+                break;
+            }
+        }
+
+        self.deadline = None;
+        self.max_nodes = None;
+        best_result.expect("The first depth always gives a result")
+    }
+
+    /// Searches the position to the depth in halfmoves
+    pub fn search(&mut self, board: &Board, depth: u32) -> SearchResult {
+        self.deadline = None;
+        self.max_nodes = None;
+        self.transposition_table.new_search();
+        self.move_ordering.new_search();
+        self.search_depth(board, depth)
+    }
+
+    fn search_depth(&mut self, board: &Board, depth: u32) -> SearchResult {
+        self.nodes = 0;
+        self.stopped = false;
+
+        let depth = depth.clamp(1, MAX_PLY);
+        let mut board = board.clone();
+        let mut pv = Vec::new();
+        let score = self.negamax(&mut board, depth, 0, -INFINITY, INFINITY, &mut pv);
+
+        SearchResult {
+            best_move: pv.first().copied(),
+            score,
+            pv,
+            depth,
+            nodes: self.nodes,
+            stopped: self.stopped,
+        }
+    }
+
+    /// Gets the score of the position from the side to move's point of view where only scores
+    /// between alpha and beta are exact. The principal variation is put in pv when a move beats
+    /// alpha.
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        depth: u32,
+        ply: u32,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        if depth == 0 {
+            pv.clear();
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(STOP_CHECK_INTERVAL) && self.should_stop() {
+            self.stopped = true;
+        }
+        if self.stopped {
+            return 0;
+        }
+
+        let moves = board.get_all_legal_moves();
+        if moves.is_empty() {
+            return if board.in_check() {
+                -MATE_SCORE + ply as i32
+            } else {
+                0
+            };
+        }
+
+        // going back to an earlier position is a draw since it can be repeated again
+        if ply > 0 && is_draw(board) {
+            return 0;
+        }
+
+        if ply >= MAX_PLY {
+            return board.evaluate();
+        }
+
+        let key = board.zobrist_key();
+        let table_entry = self.transposition_table.probe(key, ply);
+        // the move must be legal in case another position has the same key
+        let table_move = table_entry
+            .and_then(|entry| entry.best_move)
+            .filter(|table_move| moves.contains(table_move));
+
+        // the root is always searched so there is a best move
+        if let Some(entry) = table_entry.filter(|entry| ply > 0 && entry.depth as u32 >= depth) {
+            let is_cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => entry.score >= beta,
+                Bound::Upper => entry.score <= alpha,
+            };
+            if is_cutoff {
+                pv.clear();
+                pv.extend(table_move.filter(|_| entry.bound == Bound::Exact));
+                return entry.score;
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        let mut child_pv = Vec::new();
+        let mut tried_quiets = Vec::new();
+        let mut picker = MovePicker::new(board, moves, table_move, &self.move_ordering, ply);
+        while let Some(legal_move) = picker.next(board, &self.move_ordering) {
+            board.make_move(legal_move);
+            child_pv.clear();
+            let score = -self.negamax(board, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            board.unmake_move();
+
+            // the score can't be trusted when stopped but a move from the root is still needed
+            if self.stopped && (ply > 0 || !pv.is_empty()) {
+                return best_score;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(legal_move);
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(legal_move);
+                    pv.extend_from_slice(&child_pv);
+                }
+            }
+
+            if alpha >= beta {
+                if !is_tactical(board, legal_move) {
+                    self.move_ordering.on_quiet_cutoff(
+                        board,
+                        ply,
+                        depth,
+                        legal_move,
+                        &tried_quiets,
+                    );
+                }
+                break;
+            }
+
+            if !is_tactical(board, legal_move) {
+                tried_quiets.push(legal_move);
+            }
+        }
+
+        if !self.stopped {
+            let bound = if best_score >= beta {
+                Bound::Lower
+            } else if best_score > original_alpha {
+                Bound::Exact
+            } else {
+                Bound::Upper
+            };
+            self.transposition_table
+                .store(key, depth, ply, best_score, bound, best_move);
+        }
+
+        best_score
+    }
+
+    /// Searches captures and promotions until the position is quiet so it isn't evaluated in
+    /// the middle of an exchange (the horizon effect). The side to move can stand pat with the
+    /// evaluation instead of capturing unless it's in check, where every evasion is searched.
+    fn quiescence(&mut self, board: &mut Board, ply: u32, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(STOP_CHECK_INTERVAL) && self.should_stop() {
+            self.stopped = true;
+        }
+        if self.stopped {
+            return 0;
+        }
+
+        let in_check = board.in_check();
+        let mut moves = board.get_all_legal_moves();
+        if moves.is_empty() {
+            return if in_check {
+                -MATE_SCORE + ply as i32
+            } else {
+                0
+            };
+        }
+
+        if is_draw(board) {
+            return 0;
+        }
+
+        if ply >= MAX_PLY {
+            return board.evaluate();
+        }
+
+        let stand_pat = if in_check {
+            -INFINITY
+        } else {
+            board.evaluate()
+        };
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        if !in_check {
+            moves.retain(|&legal_move| is_tactical(board, legal_move));
+        }
+
+        let mut best_score = stand_pat;
+        // the picker leaves out captures that lose material once the opponent takes back
+        let mut picker = MovePicker::new_quiescence(board, moves);
+        while let Some(legal_move) = picker.next(board, &self.move_ordering) {
+            // skip captures that can't get close to alpha even when winning the piece for free
+            if !in_check && stand_pat + material_gain(board, legal_move) + DELTA_MARGIN <= alpha {
+                continue;
+            }
+
+            board.make_move(legal_move);
+            let score = -self.quiescence(board, ply + 1, -beta, -alpha);
+            board.unmake_move();
+
+            if self.stopped {
+                return best_score;
+            }
+
+            best_score = best_score.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best_score
+    }
+
+    /// Checks if the search was stopped from another thread or went past its limits
+    fn should_stop(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            || self
+                .max_nodes
+                .is_some_and(|max_nodes| self.nodes >= max_nodes)
+    }
+}
+
+impl Default for Searcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Searches the position to the depth in halfmoves
+pub fn search(board: &Board, depth: u32) -> SearchResult {
+    Searcher::new().search(board, depth)
+}
+
+/// Checks for draws that don't depend on the moves left which are the fifty move rule,
+/// insufficient material and repeating a position
+fn is_draw(board: &Board) -> bool {
+    board.halfmove_clock >= 100 || board.is_insufficient_material() || board.repetition_count() > 1
+}
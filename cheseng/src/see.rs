@@ -0,0 +1,162 @@
+use crate::eval::PIECE_VALUES;
+use crate::{Bitboard, Board, Color, Move, MoveFlag, Piece};
+
+/// The most captures there can be on one square (every piece except the kings taking in turn)
+const MAX_EXCHANGES: usize = 32;
+
+impl Board {
+    /// Gets the material in centipawns the side to move wins (or loses when negative) from the
+    /// captures on the end square of the move, where both sides take back with their least
+    /// valuable piece and can stop once taking back doesn't pay. Sliders behind other pieces
+    /// (x-rays) join in once the pieces in front have taken. Pins aren't looked at.
+    pub fn see(&self, test_move: Move) -> i32 {
+        if matches!(test_move.flag, MoveFlag::Castle(_)) {
+            return 0;
+        }
+
+        let mut occupancy = self.occupancy_after(test_move);
+        let mut gains = [0; MAX_EXCHANGES];
+        gains[0] = self.captured_value(test_move);
+        let mut attacker_value = self.mover_value(test_move);
+        let mut color = self.turn.opposite();
+        let mut num_exchanges = 1;
+
+        while num_exchanges < MAX_EXCHANGES {
+            let Some((index, kind_index)) =
+                self.least_valuable_attacker(test_move.end_index, color, occupancy)
+            else {
+                break;
+            };
+
+            // the king can't take a piece that is defended
+            let opponent_attackers =
+                self.attackers_with_occupancy(test_move.end_index, color.opposite(), occupancy);
+            if kind_index == Piece::KING && !opponent_attackers.is_empty() {
+                break;
+            }
+
+            gains[num_exchanges] = attacker_value - gains[num_exchanges - 1];
+            attacker_value = PIECE_VALUES[kind_index];
+            occupancy ^= Bitboard::from_index(index);
+            color = color.opposite();
+            num_exchanges += 1;
+        }
+
+        // each side only takes back when it's better than stopping
+        for exchange in (1..num_exchanges).rev() {
+            gains[exchange - 1] = -(-gains[exchange - 1]).max(gains[exchange]);
+        }
+        gains[0]
+    }
+
+    /// Checks if [Board::see] of the move is at least the threshold. This stops as soon as the
+    /// answer is known so it's quicker for pruning and sorting good captures from bad ones.
+    pub fn see_ge(&self, test_move: Move, threshold: i32) -> bool {
+        if matches!(test_move.flag, MoveFlag::Castle(_)) {
+            return threshold <= 0;
+        }
+
+        // the most that can be won is the captured piece when the opponent doesn't take back
+        let mut swap = self.captured_value(test_move) - threshold;
+        if swap < 0 {
+            return false;
+        }
+
+        // the least that can be won is when the opponent takes back and the exchange stops
+        swap = self.mover_value(test_move) - swap;
+        if swap <= 0 {
+            return true;
+        }
+
+        let mut occupancy = self.occupancy_after(test_move);
+        let mut color = self.turn;
+        // whether the side that made the move is winning the exchange so far
+        let mut is_winning = true;
+
+        loop {
+            color = color.opposite();
+            let Some((index, kind_index)) =
+                self.least_valuable_attacker(test_move.end_index, color, occupancy)
+            else {
+                break;
+            };
+            is_winning = !is_winning;
+
+            // the king can only take if there is nothing left to take it back
+            if kind_index == Piece::KING {
+                let opponent_attackers =
+                    self.attackers_with_occupancy(test_move.end_index, color.opposite(), occupancy);
+                return if opponent_attackers.is_empty() {
+                    is_winning
+                } else {
+                    !is_winning
+                };
+            }
+
+            swap = PIECE_VALUES[kind_index] - swap;
+            if swap < is_winning as i32 {
+                break;
+            }
+            occupancy ^= Bitboard::from_index(index);
+        }
+
+        is_winning
+    }
+
+    /// Gets the value of the piece the move captures plus what a promotion gains
+    fn captured_value(&self, test_move: Move) -> i32 {
+        let captured = match test_move.flag {
+            MoveFlag::EnPassantCapture => PIECE_VALUES[Piece::PAWN],
+            _ => self
+                .piece_at(test_move.end_index)
+                .map_or(0, |piece| PIECE_VALUES[piece.kind_index()]),
+        };
+        match test_move.flag {
+            MoveFlag::Promote(piece) => {
+                captured + PIECE_VALUES[piece.kind_index()] - PIECE_VALUES[Piece::PAWN]
+            }
+            _ => captured,
+        }
+    }
+
+    /// Gets the value of the piece on the end square after the move which is what the opponent
+    /// wins by taking back
+    fn mover_value(&self, test_move: Move) -> i32 {
+        match test_move.flag {
+            MoveFlag::Promote(piece) => PIECE_VALUES[piece.kind_index()],
+            _ => self
+                .piece_at(test_move.start_index)
+                .map_or(0, |piece| PIECE_VALUES[piece.kind_index()]),
+        }
+    }
+
+    /// Gets the occupancy with the moving piece and any pawn taken en passant gone so the
+    /// pieces behind them can attack through
+    fn occupancy_after(&self, test_move: Move) -> Bitboard {
+        let mut occupancy = self.occupancy() ^ Bitboard::from_index(test_move.start_index);
+        if test_move.flag == MoveFlag::EnPassantCapture {
+            // the taken pawn is beside the start square on the end square's file
+            let captured_index = test_move.start_index / 8 * 8 + test_move.end_index % 8;
+            occupancy ^= Bitboard::from_index(captured_index);
+        }
+        occupancy
+    }
+
+    /// Gets the index and kind of the least valuable piece of the colour that attacks the square
+    fn least_valuable_attacker(
+        &self,
+        index: u8,
+        color: Color,
+        occupancy: Bitboard,
+    ) -> Option<(u8, usize)> {
+        let attackers = self.attackers_with_occupancy(index, color, occupancy);
+        self.pieces
+            .iter()
+            .enumerate()
+            .find_map(|(kind_index, &pieces)| {
+                (attackers & pieces)
+                    .lsb()
+                    .map(|attacker_index| (attacker_index, kind_index))
+            })
+    }
+}
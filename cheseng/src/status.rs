@@ -0,0 +1,60 @@
+use crate::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameStatus {
+    Ongoing,
+    /// Checkmate with the colour of the winner
+    Checkmate(Color),
+    Stalemate,
+    /// A draw that ends the game without being claimed
+    Draw(DrawReason),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DrawReason {
+    /// 50 moves by each side without a capture or pawn move
+    FiftyMoveRule,
+    /// 75 moves by each side without a capture or pawn move
+    SeventyFiveMoveRule,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    /// Neither side has enough pieces left to checkmate
+    InsufficientMaterial,
+}
+
+impl DrawReason {
+    /// Checks if the draw only happens once a player claims it (threefold repetition and the
+    /// fifty move rule) instead of straight away
+    pub fn is_claimable(&self) -> bool {
+        matches!(self, Self::FiftyMoveRule | Self::ThreefoldRepetition)
+    }
+}
+
+impl GameStatus {
+    pub fn is_over(&self) -> bool {
+        *self != Self::Ongoing
+    }
+}
+
+impl std::fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Ongoing => write!(f, "Ongoing"),
+            Self::Checkmate(winner) => write!(f, "Checkmate, {:?} wins!", winner),
+            Self::Stalemate => write!(f, "Stalemate, it's a draw!"),
+            Self::Draw(reason) => write!(f, "{}, it's a draw!", reason),
+        }
+    }
+}
+
+impl std::fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::FiftyMoveRule => write!(f, "Fifty move rule"),
+            Self::SeventyFiveMoveRule => write!(f, "Seventy-five move rule"),
+            Self::ThreefoldRepetition => write!(f, "Threefold repetition"),
+            Self::FivefoldRepetition => write!(f, "Fivefold repetition"),
+            Self::InsufficientMaterial => write!(f, "Insufficient material"),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::search::Limits;
+use crate::Color;
+use std::time::{Duration, Instant};
+
+/// Time kept back for talking to the GUI so the clock doesn't run out while a move is being sent
+const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+/// Moves the time left is divided between when the time control doesn't say
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// Decides how long to search for. The search is always stopped at the hard limit but it only
+/// stops between depths at the soft limit, which moves with how settled the search is.
+pub(crate) struct TimeManager {
+    start_time: Instant,
+    soft_limit: Option<Duration>,
+    hard_limit: Option<Duration>,
+}
+
+impl TimeManager {
+    pub fn new(limits: &Limits, color: Color) -> Self {
+        let start_time = Instant::now();
+        if limits.infinite {
+            return Self {
+                start_time,
+                soft_limit: None,
+                hard_limit: None,
+            };
+        }
+
+        let clock_limits = limits.time_left[color.as_index()].map(|time_left| {
+            let time_left = time_left.saturating_sub(MOVE_OVERHEAD);
+            let moves_to_go = limits.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+            let increment = limits.increment[color.as_index()];
+
+            // most of the increment can be used since it's given back after the move
+            let soft_limit = (time_left / moves_to_go + increment * 3 / 4).min(time_left / 2);
+            let hard_limit = (soft_limit * 4).min(time_left / 2).max(soft_limit);
+            (soft_limit, hard_limit)
+        });
+
+        // a fixed time per move is used in full so it only has a hard limit
+        let move_time = limits
+            .move_time
+            .map(|move_time| move_time.saturating_sub(MOVE_OVERHEAD));
+
+        let hard_limit = match (clock_limits, move_time) {
+            (Some((_, clock_hard)), Some(move_time)) => Some(clock_hard.min(move_time)),
+            (Some((_, clock_hard)), None) => Some(clock_hard),
+            (None, move_time) => move_time,
+        };
+
+        Self {
+            start_time,
+            soft_limit: clock_limits.map(|(clock_soft, _)| clock_soft),
+            hard_limit,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    /// Gets when the search has to stop even in the middle of a depth
+    pub fn deadline(&self) -> Option<Instant> {
+        self.hard_limit
+            .map(|hard_limit| self.start_time + hard_limit)
+    }
+
+    /// Checks if another depth shouldn't be started. Less time is used when the best move has
+    /// stayed the same for a number of depths and more when the score drops since the last
+    /// depth (in centipawns).
+    pub fn should_stop(&self, best_move_stability: u32, score_drop: i32) -> bool {
+        let Some(soft_limit) = self.soft_limit else {
+            return false;
+        };
+
+        let stability_scale = match best_move_stability {
+            0 => 1.4,
+            1 => 1.1,
+            2 => 0.9,
+            3 => 0.75,
+            _ => 0.6,
+        };
+        let score_drop_scale = 1.0 + score_drop.clamp(0, 200) as f64 / 200.0;
+
+        let limit = soft_limit.mul_f64(stability_scale * score_drop_scale);
+        self.elapsed()
+            >= self
+                .hard_limit
+                .map_or(limit, |hard_limit| limit.min(hard_limit))
+    }
+}
@@ -0,0 +1,259 @@
+use crate::search::is_mate_score;
+use crate::Move;
+
+/// Number of entries in each bucket when it isn't given. A position can only go in the bucket
+/// its key maps to so having a few entries there lets deep and recent entries both stay.
+pub const DEFAULT_BUCKET_SIZE: usize = 4;
+
+/// What the score of an entry means compared to the real score of the position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    /// The score is the real score
+    Exact,
+    /// The real score is at least the score (a move was good enough to cut off the search)
+    Lower,
+    /// The real score is at most the score (no move beat alpha)
+    Upper,
+}
+
+/// A position saved in the table from an earlier search
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TableEntry {
+    /// Zobrist key of the position
+    pub key: u64,
+    pub best_move: Option<Move>,
+    /// Score from the side to move's point of view with mates adjusted to be from the ply the
+    /// entry was looked up at
+    pub score: i32,
+    /// Depth in halfmoves that the position was searched to
+    pub depth: u8,
+    pub bound: Bound,
+    /// The search the entry was stored in
+    pub age: u8,
+}
+
+/// Decides which entry is replaced when a position is stored
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReplacementPolicy {
+    /// Keeps deep entries from recent searches. An entry for the same position is kept when it
+    /// was searched deeper in the same search unless the new score is exact.
+    #[default]
+    DepthAndAge,
+    /// Keeps the deepest entries no matter which search they are from. An entry for the same
+    /// position is only replaced by one searched at least as deep or with an exact score.
+    DepthPreferred,
+    /// Always stores the new entry, replacing the entry from the oldest search in a full bucket
+    Always,
+}
+
+impl ReplacementPolicy {
+    /// Checks if a new entry should replace the entry for the same position
+    fn replaces_same_position(self, old_entry: TableEntry, new_entry: TableEntry) -> bool {
+        match self {
+            Self::DepthAndAge => {
+                old_entry.age != new_entry.age
+                    || old_entry.depth <= new_entry.depth
+                    || new_entry.bound == Bound::Exact
+            }
+            Self::DepthPreferred => {
+                old_entry.depth <= new_entry.depth || new_entry.bound == Bound::Exact
+            }
+            Self::Always => true,
+        }
+    }
+
+    /// How much an entry from another position is worth keeping. The entry with the lowest
+    /// priority in a full bucket is replaced.
+    fn priority(self, entry: TableEntry, age: u8) -> i32 {
+        let searches_ago = age.wrapping_sub(entry.age) as i32;
+        match self {
+            Self::DepthAndAge => entry.depth as i32 - searches_ago * 8,
+            Self::DepthPreferred => entry.depth as i32,
+            Self::Always => -searches_ago,
+        }
+    }
+}
+
+/// Hash table of searched positions keyed by their Zobrist key so positions reached by
+/// different moves (transpositions) or searched in an earlier depth aren't searched again.
+pub struct TranspositionTable {
+    /// The entries of every bucket one after the other
+    entries: Vec<Option<TableEntry>>,
+    bucket_size: usize,
+    replacement_policy: ReplacementPolicy,
+    /// Increased for each new search so entries from old searches are replaced first
+    age: u8,
+}
+
+impl TranspositionTable {
+    /// Creates a table that takes up about the size in megabytes (at least one bucket) with
+    /// the default bucket size and replacement policy
+    pub fn new(size_mb: usize) -> Self {
+        Self::with_options(size_mb, DEFAULT_BUCKET_SIZE, ReplacementPolicy::default())
+    }
+
+    /// Creates a table with the number of entries in each bucket (at least one) and the policy
+    /// for which entry is replaced when storing
+    pub fn with_options(
+        size_mb: usize,
+        bucket_size: usize,
+        replacement_policy: ReplacementPolicy,
+    ) -> Self {
+        let mut table = Self {
+            entries: Vec::new(),
+            bucket_size: bucket_size.max(1),
+            replacement_policy,
+            age: 0,
+        };
+        table.resize(size_mb);
+        table
+    }
+
+    pub fn bucket_size(&self) -> usize {
+        self.bucket_size
+    }
+
+    pub fn replacement_policy(&self) -> ReplacementPolicy {
+        self.replacement_policy
+    }
+
+    /// Changes the policy for entries stored from now on
+    pub fn set_replacement_policy(&mut self, replacement_policy: ReplacementPolicy) {
+        self.replacement_policy = replacement_policy;
+    }
+
+    /// Changes the size of the table in megabytes which clears it
+    pub fn resize(&mut self, size_mb: usize) {
+        let bucket_bytes = self.bucket_size * std::mem::size_of::<Option<TableEntry>>();
+        let num_buckets = (size_mb * 1024 * 1024 / bucket_bytes).max(1);
+        self.entries = vec![None; num_buckets * self.bucket_size];
+        self.age = 0;
+    }
+
+    /// Removes every entry (eg. for a new game)
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.age = 0;
+    }
+
+    /// Marks the start of a new search so entries from earlier searches get replaced first
+    pub fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    /// Gets the entry for the position with the score adjusted for the ply it is looked up at
+    pub fn probe(&self, key: u64, ply: u32) -> Option<TableEntry> {
+        let mut entry = self.find(key)?;
+        entry.score = score_from_table(entry.score, ply);
+        Some(entry)
+    }
+
+    /// Gets the best move found for the position to try first
+    pub fn best_move(&self, key: u64) -> Option<Move> {
+        self.find(key)?.best_move
+    }
+
+    /// Saves the position in its bucket, replacing the entry that is the least useful by the
+    /// replacement policy
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: u32,
+        ply: u32,
+        score: i32,
+        bound: Bound,
+        best_move: Option<Move>,
+    ) {
+        let age = self.age;
+        let policy = self.replacement_policy;
+        let bucket = self.bucket_range(key);
+        let entries = &mut self.entries[bucket];
+
+        let index = match entries
+            .iter()
+            .position(|entry| entry.is_some_and(|entry| entry.key == key))
+        {
+            Some(index) => index,
+            None => (0..entries.len())
+                .min_by_key(|&index| match entries[index] {
+                    Some(entry) => policy.priority(entry, age),
+                    None => i32::MIN,
+                })
+                .expect("Buckets aren't empty"),
+        };
+
+        let new_entry = TableEntry {
+            key,
+            best_move,
+            score: score_to_table(score, ply),
+            depth: depth.min(u8::MAX as u32) as u8,
+            bound,
+            age,
+        };
+
+        if let Some(old_entry) = entries[index] {
+            if old_entry.key == key {
+                if !policy.replaces_same_position(old_entry, new_entry) {
+                    return;
+                }
+
+                // keep the old best move when the new search didn't find one
+                entries[index] = Some(TableEntry {
+                    best_move: best_move.or(old_entry.best_move),
+                    ..new_entry
+                });
+                return;
+            }
+        }
+
+        entries[index] = Some(new_entry);
+    }
+
+    /// Gets how full the table is in permille from a sample of the entries from this search
+    pub fn hashfull(&self) -> u32 {
+        let sample = self.entries.len().min(1000);
+        let used = self.entries[..sample]
+            .iter()
+            .filter(|entry| entry.is_some_and(|entry| entry.age == self.age))
+            .count();
+        (used * 1000 / sample) as u32
+    }
+
+    fn find(&self, key: u64) -> Option<TableEntry> {
+        self.entries[self.bucket_range(key)]
+            .iter()
+            .flatten()
+            .find(|entry| entry.key == key)
+            .copied()
+    }
+
+    /// Gets the range of entries in the bucket for the key. The high bits of the key are used
+    /// so the number of buckets doesn't have to be a power of 2.
+    fn bucket_range(&self, key: u64) -> std::ops::Range<usize> {
+        let num_buckets = self.entries.len() / self.bucket_size;
+        let bucket = ((key as u128 * num_buckets as u128) >> 64) as usize;
+        bucket * self.bucket_size..(bucket + 1) * self.bucket_size
+    }
+}
+
+/// Mate scores are stored as the distance to mate from the position instead of from the root
+/// since the position can be reached at a different ply
+fn score_to_table(score: i32, ply: u32) -> i32 {
+    if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score + ply as i32
+    } else {
+        score - ply as i32
+    }
+}
+
+fn score_from_table(score: i32, ply: u32) -> i32 {
+    if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score - ply as i32
+    } else {
+        score + ply as i32
+    }
+}
@@ -0,0 +1,41 @@
+use cheseng::{pos, Board, Color};
+
+fn index(file: u8, rank: u8) -> u8 {
+    pos(file, rank).as_index().unwrap()
+}
+
+#[test]
+fn attackers_to_square() {
+    // white: rook a4, pawn e3, knight c2, bishop h7 (blocked by pawn g6); black: pawn g6
+    let board = Board::from_fen("4k3/7B/6p1/8/R7/4P3/2N5/4K3 w - - 0 1").unwrap();
+    let mut attackers = board.attackers_to(index(3, 4), Color::White);
+    attackers.sort();
+    assert_eq!(attackers, [index(0, 4), index(4, 5), index(2, 6)]);
+
+    assert!(board.is_square_attacked(index(6, 2), Color::White));
+    assert!(!board.is_square_attacked(index(5, 3), Color::White));
+    assert!(board.is_square_attacked(index(5, 3), Color::Black));
+}
+
+#[test]
+fn pawns_only_attack_forward() {
+    let board = Board::from_fen("4k3/8/8/3p4/3P4/8/8/4K3 w - - 0 1").unwrap();
+    assert!(board.is_square_attacked(index(2, 3), Color::White));
+    assert!(!board.is_square_attacked(index(2, 5), Color::White));
+    assert!(board.is_square_attacked(index(2, 4), Color::Black));
+    assert!(!board.is_square_attacked(index(2, 2), Color::Black));
+}
+
+#[test]
+fn checkers_of_side_to_move() {
+    let board = Board::default();
+    assert!(!board.in_check());
+    assert_eq!(board.checkers(), []);
+
+    // double check from knight f3 and rook e8
+    let board = Board::from_fen("4r1k1/8/8/8/8/5n2/8/4K3 w - - 0 1").unwrap();
+    assert!(board.in_check());
+    let mut checkers = board.checkers();
+    checkers.sort();
+    assert_eq!(checkers, [index(4, 0), index(5, 5)]);
+}
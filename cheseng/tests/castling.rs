@@ -0,0 +1,90 @@
+use cheseng::{Board, MoveFlag, Side};
+
+fn castle_sides(fen: &str) -> Vec<Side> {
+    Board::from_fen(fen)
+        .unwrap()
+        .get_all_legal_moves()
+        .into_iter()
+        .filter_map(|legal_move| match legal_move.flag {
+            MoveFlag::Castle(side) => Some(side),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn castle_both_sides() {
+    assert_eq!(
+        castle_sides("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+        [Side::Queen, Side::King]
+    );
+    assert_eq!(
+        castle_sides("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1"),
+        [Side::Queen, Side::King]
+    );
+}
+
+#[test]
+fn castle_without_rights() {
+    assert_eq!(castle_sides("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1"), []);
+}
+
+#[test]
+fn castle_through_pieces() {
+    assert_eq!(castle_sides("r3k2r/8/8/8/8/8/8/RN2K1NR w KQkq - 0 1"), []);
+    assert_eq!(castle_sides("r3k2r/8/8/8/8/8/8/R2QKB1R w KQkq - 0 1"), []);
+}
+
+#[test]
+fn castle_without_rook() {
+    // FEN parsing rejects rights without rooks so set them directly
+    for fen in [
+        "r3k2r/8/8/8/8/8/8/4K3 w kq - 0 1",
+        "r3k2r/8/8/8/8/8/8/B3K2B w kq - 0 1",
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.castle_rights = [[true; 2]; 2];
+        assert!(!board
+            .get_all_legal_moves()
+            .iter()
+            .any(|legal_move| matches!(legal_move.flag, MoveFlag::Castle(_))));
+    }
+}
+
+#[test]
+fn castle_out_of_check() {
+    assert_eq!(castle_sides("r3k2r/8/8/8/8/8/4q3/R3K2R w KQkq - 0 1"), []);
+}
+
+#[test]
+fn castle_through_check() {
+    assert_eq!(
+        castle_sides("r3k2r/8/8/8/8/8/3r4/R3K2R w KQkq - 0 1"),
+        [Side::King]
+    );
+    assert_eq!(
+        castle_sides("r3k2r/8/8/8/8/8/5r2/R3K2R w KQkq - 0 1"),
+        [Side::Queen]
+    );
+}
+
+#[test]
+fn castle_into_check() {
+    assert_eq!(
+        castle_sides("r3k2r/8/8/8/8/8/2r5/R3K2R w KQkq - 0 1"),
+        [Side::King]
+    );
+    assert_eq!(
+        castle_sides("r3k2r/8/8/8/8/8/6r1/R3K2R w KQkq - 0 1"),
+        [Side::Queen]
+    );
+}
+
+#[test]
+fn castle_with_attacked_rook_path() {
+    // only the squares the king crosses need to be safe
+    assert_eq!(
+        castle_sides("r3k2r/8/8/8/8/8/1r6/R3K2R w KQkq - 0 1"),
+        [Side::Queen, Side::King]
+    );
+}
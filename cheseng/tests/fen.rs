@@ -0,0 +1,256 @@
+use cheseng::{Board, Color, Error, FenError, FenErrorKind, FenField, Move};
+
+#[test]
+fn fen_round_trip() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        "r3k3/8/8/8/8/8/8/4K2R b Kq - 87 143",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
+
+#[test]
+fn fen_default_counters() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - -").unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+}
+
+#[test]
+fn fen_after_moves() {
+    let mut board = Board::default();
+    for (raw_move, fen) in [
+        (
+            "e2e4",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        ),
+        (
+            "g8f6",
+            "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2",
+        ),
+        (
+            "e1e2",
+            "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPPKPPP/RNBQ1BNR b kq - 2 2",
+        ),
+    ] {
+        let raw_move = raw_move.parse::<Move>().unwrap();
+        board.make_move(board.as_legal_move(raw_move).unwrap());
+        assert_eq!(board.to_fen(), fen);
+    }
+}
+
+fn fen_error(fen: &str) -> FenError {
+    match Board::from_fen(fen) {
+        Err(Error::InvalidFEN(fen_error)) => fen_error,
+        Err(err) => panic!("{} gave wrong error {:?}", fen, err),
+        Ok(_) => panic!("{} should be invalid", fen),
+    }
+}
+
+fn assert_fen_error(fen: &str, field: FenField, offset: usize, kind: FenErrorKind) {
+    assert_eq!(
+        fen_error(fen),
+        FenError {
+            field,
+            offset,
+            kind
+        },
+        "{}",
+        fen
+    );
+}
+
+#[test]
+fn fen_invalid_placement() {
+    use FenErrorKind::*;
+    assert_fen_error(
+        "4k3/9/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        4,
+        UnexpectedChar('9'),
+    );
+    assert_fen_error(
+        "4k3/7/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        5,
+        InvalidRowLength,
+    );
+    assert_fen_error(
+        "4k3/8p/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        5,
+        InvalidRowLength,
+    );
+    assert_fen_error(
+        "4k3/44/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        5,
+        UnexpectedChar('4'),
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        17,
+        InvalidNumRows,
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        17,
+        InvalidNumRows,
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K2 w - - 0 1",
+        FenField::Placement,
+        19,
+        InvalidRowLength,
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4X3 w - - 0 1",
+        FenField::Placement,
+        17,
+        UnexpectedChar('X'),
+    );
+    assert_fen_error(
+        "3pk3/8/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        1,
+        PawnOnBackRank,
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K2P w - - 0 1",
+        FenField::Placement,
+        19,
+        PawnOnBackRank,
+    );
+    assert_fen_error(
+        "8/8/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        0,
+        InvalidNumKings(Color::Black),
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/3KK3 w - - 0 1",
+        FenField::Placement,
+        18,
+        InvalidNumKings(Color::White),
+    );
+}
+
+#[test]
+fn fen_invalid_fields() {
+    use FenErrorKind::*;
+    assert_fen_error("4k3/8/8/8/8/8/8/4K3", FenField::Turn, 19, MissingField);
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K3 x - - 0 1",
+        FenField::Turn,
+        20,
+        UnexpectedStr("x".into()),
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K3 w - - x 1",
+        FenField::HalfmoveClock,
+        26,
+        UnexpectedStr("x".into()),
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 0",
+        FenField::FullmoveNumber,
+        28,
+        UnexpectedStr("0".into()),
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1 x",
+        FenField::FullmoveNumber,
+        30,
+        TooManyFields,
+    );
+}
+
+#[test]
+fn fen_opponent_in_check() {
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/r3K3 b - - 0 1",
+        FenField::Turn,
+        21,
+        FenErrorKind::OpponentInCheck,
+    );
+}
+
+#[test]
+fn fen_invalid_castle_rights() {
+    use FenErrorKind::*;
+    assert_fen_error(
+        "r3k2r/8/8/8/8/8/8/R3K2R w KQkX - 0 1",
+        FenField::CastleRights,
+        29,
+        UnexpectedChar('X'),
+    );
+    assert_fen_error(
+        "r3k2r/8/8/8/8/8/8/R3K2R w QK - 0 1",
+        FenField::CastleRights,
+        27,
+        InvalidCastleRight('K'),
+    );
+    assert_fen_error(
+        "r3k2r/8/8/8/8/8/8/R3K2R w KK - 0 1",
+        FenField::CastleRights,
+        27,
+        InvalidCastleRight('K'),
+    );
+    assert_fen_error(
+        "r3k2r/8/8/8/8/8/8/R3K3 w K - 0 1",
+        FenField::CastleRights,
+        25,
+        InvalidCastleRight('K'),
+    );
+    assert_fen_error(
+        "r3k2r/8/8/8/8/8/8/R2K3R w Q - 0 1",
+        FenField::CastleRights,
+        26,
+        InvalidCastleRight('Q'),
+    );
+    assert_fen_error(
+        "1r2k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1",
+        FenField::CastleRights,
+        28,
+        InvalidCastleRight('q'),
+    );
+}
+
+#[test]
+fn fen_invalid_en_passant() {
+    use FenErrorKind::*;
+    assert_fen_error(
+        "4k3/8/8/8/4P3/8/8/4K3 b - e9 0 1",
+        FenField::EnPassant,
+        26,
+        UnexpectedStr("e9".into()),
+    );
+    // wrong rank for the side to move
+    assert_fen_error(
+        "4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1",
+        FenField::EnPassant,
+        26,
+        InvalidEnPassant,
+    );
+    // no pawn in front
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K3 b - e3 0 1",
+        FenField::EnPassant,
+        24,
+        InvalidEnPassant,
+    );
+    // pawn couldn't have come from the starting square
+    assert_fen_error(
+        "4k3/8/8/8/4P3/8/4N3/4K3 b - e3 0 1",
+        FenField::EnPassant,
+        28,
+        InvalidEnPassant,
+    );
+}
@@ -0,0 +1,121 @@
+use cheseng::pgn::{read_games, GameResult};
+use cheseng::{Board, Game};
+
+fn play(game: &mut Game, sans: &[&str]) {
+    for san in sans {
+        let legal_move = game.board().parse_san(san).unwrap();
+        game.make_move(legal_move);
+    }
+}
+
+#[test]
+fn game_jumps_to_any_ply() {
+    let mut game = Game::new();
+    play(&mut game, &["e4", "e5", "Nf3", "Nc6"]);
+    assert_eq!(game.ply(), 4);
+    assert_eq!(game.num_plies(), 4);
+    let end_fen = game.board().to_fen();
+
+    assert!(game.go_to_ply(1));
+    assert_eq!(
+        game.board().to_fen(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+    assert_eq!(game.board().zobrist_key(), game.hashes()[1]);
+    assert_eq!(game.num_plies(), 4);
+
+    assert!(game.go_to_ply(4));
+    assert_eq!(game.board().to_fen(), end_fen);
+    assert!(!game.go_to_ply(5));
+    assert!(game.go_to_ply(0));
+    assert_eq!(game.board().to_fen(), Board::default().to_fen());
+
+    // a different move from an earlier ply replaces the rest of the game
+    game.go_to_ply(2);
+    play(&mut game, &["Bc4"]);
+    assert_eq!(game.num_plies(), 3);
+    assert_eq!(game.hashes().len(), 4);
+    assert_eq!(game.board().zobrist_key(), game.hashes()[3]);
+    assert_eq!(game.board_at(3).unwrap().to_fen(), game.board().to_fen());
+    assert!(game.board_at(4).is_none());
+}
+
+#[test]
+fn game_takes_back_moves_and_keeps_result() {
+    let mut game = Game::new();
+    play(&mut game, &["f3", "e5", "g4", "Qh4#"]);
+    assert_eq!(game.result(), GameResult::BlackWins);
+    assert_eq!(game.tag("Result"), Some("0-1"));
+
+    let taken_back = game.take_back().unwrap();
+    assert_eq!(taken_back.to_string(), "d8h4");
+    assert_eq!(game.result(), GameResult::Unknown);
+    assert_eq!(game.tag("Result"), Some("*"));
+    assert_eq!(game.num_plies(), 3);
+
+    // nothing can be taken back at the start
+    game.go_to_ply(0);
+    assert_eq!(game.take_back(), None);
+    assert_eq!(game.num_plies(), 3);
+
+    // taking back from an earlier ply cuts the game there and steps back one move
+    game.go_to_ply(2);
+    let taken_back = game.take_back().unwrap();
+    assert_eq!(taken_back.to_string(), "e7e5");
+    assert_eq!(game.ply(), 1);
+    assert_eq!(game.num_plies(), 1);
+    assert_eq!(game.hashes().len(), 2);
+    assert_eq!(game.board().zobrist_key(), game.hashes()[1]);
+    play(&mut game, &["e5"]);
+
+    game.set_result(GameResult::WhiteWins);
+    game.set_tag("White", "Someone");
+    game.set_tag("Annotator", "Someone else");
+    assert_eq!(game.tag("White"), Some("Someone"));
+    assert_eq!(game.tags.len(), 8);
+
+    let pgn = game.to_pgn().to_string();
+    assert!(pgn.contains("[White \"Someone\"]\n"));
+    assert!(pgn.contains("[Annotator \"Someone else\"]\n"));
+    assert!(pgn.ends_with("\n1. f3 e5 1-0\n"));
+
+    while game.take_back().is_some() {}
+    assert_eq!(game.ply(), 0);
+    assert_eq!(game.hashes(), [Board::default().zobrist_key()]);
+}
+
+#[test]
+fn game_converts_to_and_from_pgn() {
+    let pgn = r#"[White "A"]
+[Black "B"]
+[Result "1/2-1/2"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/8/R3K3 b Q - 0 30"]
+
+30... Kd7 31. O-O-O+ Kc6 1/2-1/2"#;
+    let pgn_game = &read_games(pgn).unwrap()[0];
+    let game = Game::from_pgn(pgn_game).unwrap();
+    assert_eq!(
+        game.starting_board().to_fen(),
+        "4k3/8/8/8/8/8/8/R3K3 b Q - 0 30"
+    );
+    assert_eq!(game.tag("FEN"), None);
+    assert_eq!(game.tag("White"), Some("A"));
+    assert_eq!(game.result(), GameResult::Draw);
+    assert_eq!(game.num_plies(), 3);
+
+    let written = game.to_pgn();
+    assert_eq!(written.tag("FEN"), Some("4k3/8/8/8/8/8/8/R3K3 b Q - 0 30"));
+    assert_eq!(written.result, GameResult::Draw);
+    assert!(written
+        .to_string()
+        .ends_with("\n30... Kd7 31. O-O-O+ Kc6 1/2-1/2\n"));
+
+    // the moves made on a board become the moves of the game
+    let mut board = Board::default();
+    let legal_move = board.parse_san("d4").unwrap();
+    board.make_move(legal_move);
+    let game = Game::from_board(board);
+    assert_eq!(game.num_plies(), 1);
+    assert_eq!(game.starting_board().to_fen(), Board::default().to_fen());
+}
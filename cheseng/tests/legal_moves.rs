@@ -0,0 +1,125 @@
+use cheseng::{Board, Position};
+
+fn square_name(index: u8) -> String {
+    let position = Position::from_index(index);
+    format!(
+        "{}{}",
+        (b'a' + position.file) as char,
+        (b'8' - position.rank) as char
+    )
+}
+
+/// Gets the legal moves as start and end squares (eg. e2e4) in sorted order
+fn legal_moves(fen: &str) -> Vec<String> {
+    let board = Board::from_fen(fen).unwrap();
+    let mut moves: Vec<String> = board
+        .get_all_legal_moves()
+        .into_iter()
+        .map(|legal_move| square_name(legal_move.start_index) + &square_name(legal_move.end_index))
+        .collect();
+    moves.sort();
+    moves
+}
+
+fn legal_moves_from(fen: &str, square: &str) -> Vec<String> {
+    legal_moves(fen)
+        .into_iter()
+        .filter(|legal_move| legal_move.starts_with(square))
+        .collect()
+}
+
+#[test]
+fn pinned_pieces_stay_on_the_pin_line() {
+    // the bishop can't move at all since it can't stay on the file
+    assert!(legal_moves_from("4k3/4r3/8/8/8/8/4B3/4K3 w - - 0 1", "e2").is_empty());
+    // the rook can move along the file up to taking the pinning rook
+    assert_eq!(
+        legal_moves_from("4k3/4r3/8/8/8/8/4R3/4K3 w - - 0 1", "e2"),
+        ["e2e3", "e2e4", "e2e5", "e2e6", "e2e7"]
+    );
+    // a knight pinned on a diagonal can't move
+    assert!(legal_moves_from("4k3/8/8/8/b7/8/2N5/3K4 w - - 0 1", "c2").is_empty());
+    // a pawn pinned on a diagonal can only take the pinning piece
+    assert_eq!(
+        legal_moves_from("4k3/8/8/8/8/2b5/3P4/4K3 w - - 0 1", "d2"),
+        ["d2c3"]
+    );
+}
+
+#[test]
+fn pieces_that_block_discovered_checks_stay() {
+    // moving the knight would uncover the bishop's check
+    assert!(legal_moves_from("4k3/8/8/8/7b/8/5N2/4K3 w - - 0 1", "f2").is_empty());
+    // a pawn in front of the king can't capture off the file
+    assert_eq!(
+        legal_moves_from("4k3/8/8/8/4r3/3b4/4P3/4K3 w - - 0 1", "e2"),
+        ["e2e3"]
+    );
+}
+
+#[test]
+fn only_king_moves_escape_double_check() {
+    // the rook and knight both give check so taking one of them isn't enough
+    let moves = legal_moves("4k3/8/8/8/8/3n4/2B5/4K2r w - - 0 1");
+    assert!(moves.iter().all(|legal_move| legal_move.starts_with("e1")));
+    assert!(!moves.contains(&"c2d3".to_owned()));
+}
+
+#[test]
+fn check_evasions_block_capture_or_move_the_king() {
+    let moves = legal_moves("4k3/8/8/8/8/8/1B6/r3K3 w - - 0 1");
+    // the king can't stay on the rook's rank or step along it
+    assert!(!moves.contains(&"e1d1".to_owned()));
+    assert!(!moves.contains(&"e1f1".to_owned()));
+    // the bishop can only take the rook or block
+    assert_eq!(
+        legal_moves_from("4k3/8/8/8/8/8/1B6/r3K3 w - - 0 1", "b2"),
+        ["b2a1", "b2c1"]
+    );
+    assert!(moves.contains(&"e1e2".to_owned()));
+}
+
+#[test]
+fn en_passant_that_uncovers_the_king_on_its_rank_is_illegal() {
+    // taking en passant moves both pawns off the rank between the king and the rook
+    let fen = "8/8/8/K2pP2r/8/8/8/4k3 w - d6 0 1";
+    assert_eq!(legal_moves_from(fen, "e5"), ["e5e6"]);
+
+    // without the rook it is legal
+    let fen = "8/8/8/K2pP3/8/8/8/4k3 w - d6 0 1";
+    assert_eq!(legal_moves_from(fen, "e5"), ["e5d6", "e5e6"]);
+}
+
+#[test]
+fn piece_moves_match_the_moves_of_the_whole_board() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        // in check and in double check
+        "4k3/8/8/8/8/3n4/8/R3K2R w KQ - 0 1",
+        "4k3/8/8/8/1b6/5n2/8/R3K2R w KQ - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let all_moves = board.get_all_legal_moves();
+        for (index, piece) in board.grid().iter().enumerate() {
+            let Some(piece) = piece else {
+                continue;
+            };
+
+            let index = index as u8;
+            let expected: Vec<_> = all_moves
+                .iter()
+                .filter(|legal_move| legal_move.start_index == index)
+                .copied()
+                .collect();
+            assert_eq!(
+                piece.get_legal_moves(index, &board),
+                expected,
+                "{} {}",
+                fen,
+                square_name(index)
+            );
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use cheseng::{Board, Color, Error, Move, MoveFlag, Piece};
+
+#[test]
+fn move_parse_and_display() {
+    let parsed_move = "e2e4".parse::<Move>().unwrap();
+    assert_eq!(parsed_move, Move::new(52, 36));
+    assert_eq!(parsed_move.to_string(), "e2e4");
+
+    let parsed_move = "e7e8n".parse::<Move>().unwrap();
+    assert_eq!(
+        parsed_move,
+        Move::new(12, 4).flag(MoveFlag::Promote(Piece::Knight(Color::White)))
+    );
+    assert_eq!(parsed_move.to_string(), "e7e8n");
+
+    let parsed_move = "a2a1q".parse::<Move>().unwrap();
+    assert_eq!(
+        parsed_move.flag,
+        MoveFlag::Promote(Piece::Queen(Color::Black))
+    );
+}
+
+#[test]
+fn move_parse_errors() {
+    for (input, expected_char) in [("e2e9", '9'), ("i2e4", 'i'), ("é2e4", 'é'), ("e2é4", 'é')] {
+        match input.parse::<Move>() {
+            Err(Error::InvalidPosition(char)) => {
+                assert_eq!(char, Some(expected_char), "{}", input)
+            }
+            result => panic!(
+                "expected invalid position for {} but got {:?}",
+                input, result
+            ),
+        }
+    }
+
+    // moves that are too short or too long don't have an unexpected char
+    for (input, expected_char) in [
+        ("", None),
+        ("e", None),
+        ("e2", None),
+        ("e2e", None),
+        ("e7e8qq", None),
+        ("e7e8q ", None),
+        ("e7e8k", Some('k')),
+        ("e7e8x", Some('x')),
+    ] {
+        match input.parse::<Move>() {
+            Err(Error::InvalidMove(char)) => assert_eq!(char, expected_char, "{}", input),
+            result => panic!("expected invalid move for {} but got {:?}", input, result),
+        }
+    }
+}
+
+#[test]
+fn move_display_round_trips_legal_moves() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "4k3/8/8/8/8/8/1p6/4K3 b - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        for legal_move in board.get_all_legal_moves() {
+            let parsed_move = legal_move.to_string().parse::<Move>().unwrap();
+            assert_eq!(board.as_legal_move(parsed_move), Some(legal_move));
+        }
+    }
+}
+
+#[test]
+fn as_legal_move_uses_promotion_piece() {
+    let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let as_legal_move = |input: &str| board.as_legal_move(input.parse().unwrap());
+
+    for (input, piece) in [
+        ("b7b8q", Piece::Queen(Color::White)),
+        ("b7b8r", Piece::Rook(Color::White)),
+        ("b7b8b", Piece::Bishop(Color::White)),
+        ("b7b8n", Piece::Knight(Color::White)),
+        // queen when the piece isn't given
+        ("b7b8", Piece::Queen(Color::White)),
+    ] {
+        assert_eq!(
+            as_legal_move(input).unwrap().flag,
+            MoveFlag::Promote(piece),
+            "{}",
+            input
+        );
+    }
+
+    assert_eq!(as_legal_move("e1e2q"), None);
+}
@@ -0,0 +1,83 @@
+use cheseng::perft::{perft, perft_divide};
+use cheseng::Board;
+
+// Positions and node counts from https://www.chessprogramming.org/Perft_Results
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str =
+    "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+fn assert_perft(fen: &str, expected_counts: &[u64]) {
+    let board = Board::from_fen(fen).unwrap();
+    for (depth, &expected_count) in expected_counts.iter().enumerate() {
+        assert_eq!(
+            perft(&board, depth as u32 + 1),
+            expected_count,
+            "depth {} of {}",
+            depth + 1,
+            fen
+        );
+    }
+}
+
+#[test]
+fn perft_start_position() {
+    assert_perft(START, &[20, 400, 8_902, 197_281, 4_865_609]);
+}
+
+#[test]
+fn perft_kiwipete() {
+    assert_perft(KIWIPETE, &[48, 2_039, 97_862, 4_085_603]);
+}
+
+#[test]
+fn perft_position_3() {
+    assert_perft(POSITION_3, &[14, 191, 2_812, 43_238, 674_624]);
+}
+
+#[test]
+fn perft_position_4() {
+    assert_perft(POSITION_4, &[6, 264, 9_467, 422_333]);
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9_467, 422_333]);
+}
+
+#[test]
+fn perft_position_5() {
+    assert_perft(POSITION_5, &[44, 1_486, 62_379, 2_103_487]);
+}
+
+#[test]
+fn perft_position_6() {
+    assert_perft(POSITION_6, &[46, 2_079, 89_890, 3_894_594]);
+}
+
+#[test]
+fn perft_depth_zero() {
+    let board = Board::default();
+    assert_eq!(perft(&board, 0), 1);
+    assert!(perft_divide(&board, 0).is_empty());
+}
+
+#[test]
+fn perft_divide_adds_up() {
+    let board = Board::from_fen(KIWIPETE).unwrap();
+    let divide = perft_divide(&board, 3);
+    assert_eq!(divide.len(), 48);
+    assert_eq!(divide.iter().map(|(_, count)| count).sum::<u64>(), 97_862);
+
+    // castling kingside (e1g1) and queenside (e1c1)
+    let count_for = |start_index, end_index| {
+        divide
+            .iter()
+            .find(|(divide_move, _)| {
+                (divide_move.start_index, divide_move.end_index) == (start_index, end_index)
+            })
+            .map(|&(_, count)| count)
+    };
+    assert_eq!(count_for(60, 62), Some(2_059));
+    assert_eq!(count_for(60, 58), Some(1_887));
+}
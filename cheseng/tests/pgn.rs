@@ -0,0 +1,239 @@
+use cheseng::pgn::{read_games, write_games, Evaluation, GameResult, PgnGame};
+use cheseng::{Board, Error, PgnErrorKind, SanError};
+use std::time::Duration;
+
+const TWO_GAMES: &str = r#"[Event "F/S Return Match"]
+[Site "Belgrade, Serbia JUG"]
+[Date "1992.11.04"]
+[Round "29"]
+[White "Fischer, Robert J."]
+[Black "Spassky, Boris V."]
+[Result "1/2-1/2"]
+[Annotator "Someone \"quoted\""]
+
+{Opening comment} 1. e4 e5 2. Nf3 Nc6 3. Bb5 {This opening is called the Ruy Lopez.} 3... a6
+4. Ba4 Nf6 5. O-O Be7 6. Re1 b5 7. Bb3 d6 8. c3 O-O 9. h3 Nb8 10. d4 Nbd7
+11. c4 c6 12. cxb5 axb5 13. Nc3 Bb7 14. Bg5 b4 15. Nb1 h6 16. Bh4 c5 17. dxe5
+Nxe4 18. Bxe7 Qxe7 19. exd6 Qf6 20. Nbd2 Nxd6 21. Nc4 Nxc4 22. Bxc4 Nb6
+23. Ne5 Rae8 24. Bxf7+ Rxf7 25. Nxf7 Rxe1+ 26. Qxe1 Kxf7 27. Qe3 Qg5 28. Qxg5
+hxg5 29. b3 Ke6 30. a3 Kd6 31. axb4 cxb4 32. Ra5 Nd5 33. f3 Bc8 34. Kf2 Bf5
+35. Ra7 g6 36. Ra6+ Kc5 37. Ke1 Nf4 38. g3 Nxh3 39. Kd2 Kb5 40. Rd6 Kc5 41. Ra6
+Nf2 42. g4 Bd3 43. Re6 1/2-1/2
+
+% an escaped line that isn't PGN
+[Event "Annotated"]
+[Site "?"]
+[Result "*"]
+
+1.e4 $1 e5!? 2. Nf3 (2. f4 exf4 (2... d5 {the Falkbeer} 3. exd5) 3. Nf3) ; line comment
+2... Nc6?? *
+"#;
+
+#[test]
+fn pgn_reads_tags_and_moves() {
+    let games = read_games(TWO_GAMES).unwrap();
+    assert_eq!(games.len(), 2);
+
+    let game = &games[0];
+    assert_eq!(game.tag("White"), Some("Fischer, Robert J."));
+    assert_eq!(game.tag("Round"), Some("29"));
+    assert_eq!(game.tag("Annotator"), Some("Someone \"quoted\""));
+    assert_eq!(game.tag("Missing"), None);
+    assert_eq!(game.tags.len(), 8);
+    assert_eq!(game.result, GameResult::Draw);
+    assert_eq!(game.moves.len(), 85);
+
+    assert_eq!(game.moves[0].comments_before, ["Opening comment"]);
+    assert_eq!(
+        game.moves[4].comments_after,
+        ["This opening is called the Ruy Lopez."]
+    );
+    assert_eq!(game.moves[8].san, "O-O");
+    assert_eq!(game.moves[46].san, "Bxf7+");
+
+    assert_eq!(
+        game.board().unwrap().to_fen(),
+        "8/8/4R1p1/2k3p1/1p4P1/1P1b1P2/3K1n2/8 b - - 2 43"
+    );
+}
+
+#[test]
+fn pgn_reads_annotations_and_variations() {
+    let games = read_games(TWO_GAMES).unwrap();
+    let game = &games[1];
+    assert_eq!(game.result, GameResult::Unknown);
+    assert_eq!(game.moves.len(), 4);
+
+    assert_eq!(game.moves[0].nags, [1]);
+    assert_eq!(game.moves[1].nags, [5]);
+    assert_eq!(game.moves[3].nags, [4]);
+    assert_eq!(game.moves[2].comments_after, ["line comment"]);
+
+    // 2. f4 replaces 2. Nf3 and 2... d5 replaces 2... exf4 inside it
+    let variations = &game.moves[2].variations;
+    assert_eq!(variations.len(), 1);
+    let sans: Vec<&str> = variations[0].iter().map(|node| node.san.as_str()).collect();
+    assert_eq!(sans, ["f4", "exf4", "Nf3"]);
+
+    let nested = &variations[0][1].variations[0];
+    let sans: Vec<&str> = nested.iter().map(|node| node.san.as_str()).collect();
+    assert_eq!(sans, ["d5", "exd5"]);
+    assert_eq!(nested[0].comments_after, ["the Falkbeer"]);
+
+    assert_eq!(
+        game.board().unwrap().to_fen(),
+        "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3"
+    );
+}
+
+#[test]
+fn pgn_starts_from_fen_tag() {
+    let pgn = r#"[SetUp "1"]
+[FEN "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1"]
+
+1. b8=N Kf7 2. Kd2 1-0"#;
+    let games = read_games(pgn).unwrap();
+    assert_eq!(games[0].result, GameResult::WhiteWins);
+    assert_eq!(games[0].moves[0].san, "b8=N");
+    assert_eq!(
+        games[0].board().unwrap().to_fen(),
+        "1N6/5k2/8/8/8/8/3K4/8 b - - 2 2"
+    );
+}
+
+#[test]
+fn pgn_without_termination_uses_result_tag() {
+    let games =
+        read_games("[Result \"0-1\"]\n1. f3 e5 2. g4 Qh4#\n[Event \"Next\"]\n1. d4 *").unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].result, GameResult::BlackWins);
+    assert_eq!(games[0].moves[3].san, "Qh4#");
+    assert_eq!(games[1].moves.len(), 1);
+    assert!(read_games("").unwrap().is_empty());
+}
+
+fn assert_pgn_error(pgn: &str, line: usize, column: usize, kind: PgnErrorKind) {
+    match read_games(pgn) {
+        Err(Error::InvalidPGN(pgn_error)) => {
+            assert_eq!(
+                (pgn_error.line, pgn_error.column, pgn_error.kind),
+                (line, column, kind),
+                "{}",
+                pgn
+            )
+        }
+        result => panic!("expected error for {} but got {:?}", pgn, result),
+    }
+}
+
+#[test]
+fn pgn_errors() {
+    assert_pgn_error(
+        "1. e4 e5\n2. Ke3 *",
+        2,
+        4,
+        PgnErrorKind::InvalidMove("Ke3".into(), SanError::IllegalMove),
+    );
+    assert_pgn_error("[Event \"x]\n1. e4 *", 1, 1, PgnErrorKind::InvalidTag);
+    assert_pgn_error("[Event x]\n*", 1, 1, PgnErrorKind::InvalidTag);
+    assert_pgn_error("1. e4 {unterminated", 1, 7, PgnErrorKind::UnexpectedEnd);
+    assert_pgn_error("1. e4 (1. d4", 1, 13, PgnErrorKind::UnexpectedEnd);
+    assert_pgn_error("1. e4 ) *", 1, 7, PgnErrorKind::UnexpectedChar(')'));
+    assert_pgn_error("(1. d4) 1. e4 *", 1, 1, PgnErrorKind::VariationWithoutMove);
+    assert_pgn_error("1. e4 () *", 1, 7, PgnErrorKind::EmptyVariation);
+    assert_pgn_error("1. e4 (1. d4 1-0) *", 1, 14, PgnErrorKind::UnexpectedResult);
+    assert_pgn_error("$1 1. e4 *", 1, 1, PgnErrorKind::NagWithoutMove);
+    assert_pgn_error("1. e4 $x *", 1, 7, PgnErrorKind::InvalidNag);
+    assert_pgn_error("1. e4!!! *", 1, 4, PgnErrorKind::InvalidNag);
+
+    match read_games("[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n*") {
+        Err(Error::InvalidPGN(pgn_error)) => {
+            assert!(matches!(pgn_error.kind, PgnErrorKind::InvalidFen(_)));
+            assert_eq!((pgn_error.line, pgn_error.column), (1, 1));
+        }
+        result => panic!("expected FEN error but got {:?}", result),
+    }
+}
+
+#[test]
+fn pgn_writes_annotations_and_variations() {
+    let games = read_games(TWO_GAMES).unwrap();
+    let pgn = games[1].to_string();
+    assert_eq!(
+        pgn,
+        r#"[Event "Annotated"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "*"]
+
+1. e4 $1 e5 $5 2. Nf3 {line comment} (2. f4 exf4 (2... d5 {the Falkbeer} 3.
+exd5) 3. Nf3) 2... Nc6 $4 *
+"#
+    );
+
+    // writing what was read gives back the same games
+    let written = write_games(&games);
+    let reread = read_games(&written).unwrap();
+    assert_eq!(reread.len(), 2);
+    assert_eq!(write_games(&reread), written);
+    assert_eq!(reread[0].tag("Annotator"), Some("Someone \"quoted\""));
+    assert_eq!(
+        reread[0].board().unwrap().to_fen(),
+        games[0].board().unwrap().to_fen()
+    );
+    assert!(written.lines().all(|line| line.len() <= 80));
+}
+
+#[test]
+fn pgn_writes_eval_and_clock_commands() {
+    let pgn = "1. e4 {[%eval 0.25] [%clk 1:30:00] good} e5 {[%eval #-3] [%clk 0:00:09.5]} *";
+    let game = &read_games(pgn).unwrap()[0];
+    assert_eq!(game.moves[0].eval, Some(Evaluation::Centipawns(25)));
+    assert_eq!(game.moves[0].clock, Some(Duration::from_secs(5400)));
+    assert_eq!(game.moves[1].eval, Some(Evaluation::Mate(-3)));
+    assert_eq!(game.moves[1].clock, Some(Duration::from_millis(9500)));
+
+    // the movetext is wrapped before it reaches 80 columns
+    let pgn = game.to_string();
+    let movetext: Vec<&str> = pgn
+        .lines()
+        .skip_while(|line| !line.is_empty())
+        .skip(1)
+        .collect();
+    assert_eq!(
+        movetext,
+        [
+            "1. e4 {[%eval 0.25] [%clk 1:30:00]} {good} 1... e5 {[%eval #-3] [%clk",
+            "0:00:09.5]} *"
+        ]
+    );
+}
+
+#[test]
+fn pgn_from_board_numbers_moves_from_fen() {
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 30").unwrap();
+    for san in ["Kd7", "O-O-O+", "Kc6"] {
+        let legal_move = board.parse_san(san).unwrap();
+        board.make_move(legal_move);
+    }
+
+    let game = PgnGame::from_board(&board);
+    assert_eq!(game.tag("SetUp"), Some("1"));
+    assert_eq!(game.tag("FEN"), Some("4k3/8/8/8/8/8/8/R3K3 b Q - 0 30"));
+    assert_eq!(game.result, GameResult::Unknown);
+    assert!(game.to_string().ends_with("\n30... Kd7 31. O-O-O+ Kc6 *\n"));
+
+    // a finished game gets its result
+    let mut board = Board::default();
+    for san in ["f3", "e5", "g4", "Qh4#"] {
+        let legal_move = board.parse_san(san).unwrap();
+        board.make_move(legal_move);
+    }
+    let game = PgnGame::from_board(&board);
+    assert_eq!(game.tag("FEN"), None);
+    assert_eq!(game.tag("Result"), Some("0-1"));
+    assert!(game.to_string().ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+}
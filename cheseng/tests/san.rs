@@ -0,0 +1,143 @@
+use cheseng::{Board, Error, SanError};
+
+fn san_of(fen: &str, raw_move: &str) -> String {
+    let board = Board::from_fen(fen).unwrap();
+    let legal_move = board.as_legal_move(raw_move.parse().unwrap()).unwrap();
+    board.move_to_san(legal_move)
+}
+
+fn parse_san(fen: &str, san: &str) -> Result<(u8, u8), Error> {
+    Board::from_fen(fen)
+        .unwrap()
+        .parse_san(san)
+        .map(|legal_move| (legal_move.start_index, legal_move.end_index))
+}
+
+fn assert_san_error(fen: &str, san: &str, expected_error: SanError) {
+    match parse_san(fen, san) {
+        Err(Error::InvalidSAN(san_error)) => assert_eq!(san_error, expected_error, "{}", san),
+        result => panic!(
+            "expected {:?} for {} but got {:?}",
+            expected_error, san, result
+        ),
+    }
+}
+
+#[test]
+fn san_piece_and_pawn_moves() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(san_of(start, "g1f3"), "Nf3");
+    assert_eq!(san_of(start, "e2e4"), "e4");
+    assert_eq!(parse_san(start, "Nf3").unwrap(), (62, 45));
+    assert_eq!(parse_san(start, "e4").unwrap(), (52, 36));
+}
+
+#[test]
+fn san_captures() {
+    let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+    assert_eq!(san_of(fen, "e5f6"), "exf6");
+    assert_eq!(parse_san(fen, "exf6").unwrap(), (28, 21));
+
+    let fen = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2";
+    assert_eq!(san_of(fen, "e4d5"), "exd5");
+    assert_eq!(san_of(fen, "f1b5"), "Bb5+");
+}
+
+#[test]
+fn san_disambiguation() {
+    // file, rank and then both when neither is enough on its own
+    let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+    assert_eq!(san_of(fen, "b1d2"), "Nbd2");
+    assert_eq!(san_of(fen, "f3d2"), "Nfd2");
+    assert_eq!(parse_san(fen, "Nbd2").unwrap(), (57, 51));
+
+    let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(san_of(fen, "a1a3"), "R1a3");
+    assert_eq!(san_of(fen, "a5a3"), "R5a3");
+    assert_eq!(parse_san(fen, "R5a3").unwrap(), (24, 40));
+
+    let fen = "4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1";
+    assert_eq!(san_of(fen, "a1b2"), "Qa1b2");
+    assert_eq!(san_of(fen, "c1b2"), "Qcb2");
+    assert_eq!(parse_san(fen, "Qa1b2").unwrap(), (56, 49));
+}
+
+#[test]
+fn san_promotions() {
+    let fen = "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1";
+    let board = Board::from_fen(fen).unwrap();
+    let sans: Vec<String> = board
+        .get_all_legal_moves()
+        .into_iter()
+        .filter(|legal_move| legal_move.start_index == 9)
+        .map(|legal_move| board.move_to_san(legal_move))
+        .collect();
+    assert_eq!(sans, ["b8=Q+", "b8=R+", "b8=B", "b8=N"]);
+
+    for san in ["b8=Q", "b8Q", "b8=N"] {
+        assert_eq!(parse_san(fen, san).unwrap(), (9, 1));
+    }
+    assert_san_error(fen, "b8", SanError::IllegalMove);
+    assert_san_error(fen, "b8=K", SanError::InvalidNotation);
+}
+
+#[test]
+fn san_castling() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    assert_eq!(san_of(fen, "e1g1"), "O-O");
+    assert_eq!(san_of(fen, "e1c1"), "O-O-O");
+    assert_eq!(parse_san(fen, "O-O").unwrap(), (60, 62));
+    assert_eq!(parse_san(fen, "0-0-0").unwrap(), (60, 58));
+    assert_san_error(
+        "r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1",
+        "O-O",
+        SanError::IllegalMove,
+    );
+}
+
+#[test]
+fn san_check_and_mate_suffixes() {
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+    assert_eq!(san_of(fen, "h5f7"), "Qxf7#");
+    assert_eq!(parse_san(fen, "Qxf7#").unwrap(), (31, 13));
+    assert_eq!(parse_san(fen, "Qxf7").unwrap(), (31, 13));
+    assert_eq!(parse_san(fen, "Qf7+!?").unwrap(), (31, 13));
+}
+
+#[test]
+fn san_errors() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_san_error(start, "e5", SanError::IllegalMove);
+    assert_san_error(start, "Ke2", SanError::IllegalMove);
+    for san in ["", "Z", "Nf", "Nf9", "Xf3", "Nzf3", "e2e4x"] {
+        assert_san_error(start, san, SanError::InvalidNotation);
+    }
+
+    assert_san_error(
+        "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1",
+        "Nd2",
+        SanError::AmbiguousMove,
+    );
+}
+
+#[test]
+fn san_round_trip() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        for legal_move in board.get_all_legal_moves() {
+            let san = board.move_to_san(legal_move);
+            let parsed_move = board.parse_san(&san).unwrap();
+            assert_eq!(
+                (parsed_move.start_index, parsed_move.end_index),
+                (legal_move.start_index, legal_move.end_index),
+                "{}",
+                san
+            );
+            assert_eq!(board.move_to_san(parsed_move), san);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use cheseng::search::{search, Limits, Score, Searcher, MATE_SCORE};
+use cheseng::Board;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+/// Checks the search finds one of the best moves (there can be more than one way to mate)
+fn assert_best_move(fen: &str, depth: u32, best_moves: &[&str], score: Score) {
+    let board = Board::from_fen(fen).unwrap();
+    let result = search(&board, depth);
+    let best_move = result.best_move.unwrap().to_string();
+    assert!(
+        best_moves.contains(&best_move.as_str()),
+        "{} {}",
+        fen,
+        best_move
+    );
+    assert_eq!(Score::from_search_score(result.score), score, "{}", fen);
+    assert!(!result.stopped);
+
+    // the principal variation should be legal moves starting with the best move
+    let mut board = board;
+    assert_eq!(result.pv.first(), result.best_move.as_ref());
+    for pv_move in result.pv {
+        assert_eq!(board.as_legal_move(pv_move), Some(pv_move), "{}", fen);
+        board.make_move(pv_move);
+    }
+}
+
+#[test]
+fn search_finds_mates() {
+    // scholar's mate
+    assert_best_move(
+        "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR w KQkq - 2 3",
+        3,
+        &["f3f7"],
+        Score::Mate(1),
+    );
+    assert_best_move(
+        "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1",
+        2,
+        &["a1a8"],
+        Score::Mate(1),
+    );
+    assert_best_move(
+        "k7/8/2K5/8/8/8/8/7R w - - 0 1",
+        4,
+        &["c6c7", "c6b6"],
+        Score::Mate(2),
+    );
+
+    // the side getting mated sees it coming
+    let board = Board::from_fen("k7/2K5/8/8/8/8/8/7R b - - 1 1").unwrap();
+    let result = search(&board, 3);
+    assert_eq!(result.best_move.unwrap().to_string(), "a8a7");
+    assert_eq!(result.score, -MATE_SCORE + 2);
+    assert_eq!(Score::from_search_score(result.score), Score::Mate(-1));
+}
+
+#[test]
+fn search_scores_checkmate_and_stalemate() {
+    let board = Board::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    let result = search(&board, 3);
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, -MATE_SCORE);
+    assert_eq!(Score::from_search_score(result.score), Score::Mate(0));
+
+    let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    let result = search(&board, 3);
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, 0);
+}
+
+#[test]
+fn search_wins_material() {
+    let board = Board::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+    let result = search(&board, 2);
+    assert_eq!(result.best_move.unwrap().to_string(), "d2d5");
+    assert!(
+        matches!(Score::from_search_score(result.score), Score::Centipawns(score) if score > 400)
+    );
+
+    // the rook is defended so taking it loses the queen
+    let board = Board::from_fen("4k3/8/4p3/3r4/8/8/3Q4/4K3 w - - 0 1").unwrap();
+    let result = search(&board, 2);
+    assert_ne!(result.best_move.unwrap().to_string(), "d2d5");
+}
+
+#[test]
+fn search_looks_past_captures() {
+    // taking the defended rook loses the queen which is seen even at depth 1
+    let board = Board::from_fen("4k3/8/4p3/3r4/8/8/3Q4/4K3 w - - 0 1").unwrap();
+    let result = search(&board, 1);
+    assert_ne!(result.best_move.unwrap().to_string(), "d2d5");
+
+    // the queens come off and white wins the rook so the score doesn't change with the depth
+    let board = Board::from_fen("3rk3/8/8/3q4/8/8/3Q4/3RK3 w - - 0 1").unwrap();
+    for depth in 1..=4 {
+        let result = search(&board, depth);
+        assert_eq!(result.best_move.unwrap().to_string(), "d2d5");
+        assert!((result.score - 500).abs() < 50, "depth {}", depth);
+    }
+}
+
+#[test]
+fn quiescence_leaves_out_captures_that_lose_material() {
+    // the king's only move defends the pawn so the queen taking it loses the queen and isn't
+    // searched, leaving as many nodes as when the queen can't reach the pawn
+    let losing_capture = Board::from_fen("k5r1/3q4/8/8/7p/7P/8/7K w - - 0 1").unwrap();
+    let no_capture = Board::from_fen("k2q2r1/8/8/8/7p/7P/8/7K w - - 0 1").unwrap();
+    assert_eq!(
+        search(&losing_capture, 1).nodes,
+        search(&no_capture, 1).nodes
+    );
+
+    // a capture that doesn't lose material is searched
+    let even_capture = Board::from_fen("k5r1/8/8/8/6pp/7P/8/7K w - - 0 1").unwrap();
+    let no_capture = Board::from_fen("k5r1/8/8/6p1/7p/7P/8/7K w - - 0 1").unwrap();
+    assert!(search(&even_capture, 1).nodes > search(&no_capture, 1).nodes);
+}
+
+#[test]
+fn search_tries_the_best_moves_first() {
+    // alpha-beta only cuts off early enough to stay under the bound when good moves come first
+    let board =
+        Board::from_fen("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4")
+            .unwrap();
+    let result = search(&board, 5);
+    assert!(result.nodes < 200000, "{}", result.nodes);
+}
+
+#[test]
+fn think_searches_deeper_until_limits() {
+    let board = Board::default();
+    let mut depths = Vec::new();
+    let limits = Limits {
+        depth: Some(4),
+        ..Limits::default()
+    };
+    let result = Searcher::new().think(&board, &limits, |info| depths.push(info.depth));
+    assert_eq!(depths, [1, 2, 3, 4]);
+    assert_eq!(result.depth, 4);
+    assert!(result.best_move.is_some());
+
+    // stops as soon as a mate is found
+    let board = Board::from_fen("k7/8/2K5/8/8/8/8/7R w - - 0 1").unwrap();
+    let result = Searcher::new().think(&board, &Limits::default(), |_| ());
+    assert_eq!(result.depth, 3);
+    assert_eq!(Score::from_search_score(result.score), Score::Mate(2));
+
+    let limits = Limits {
+        nodes: Some(10000),
+        ..Limits::default()
+    };
+    let result = Searcher::new().think(&Board::default(), &limits, |_| ());
+    assert!(result.best_move.is_some());
+    assert!(result.nodes < 20000);
+}
+
+#[test]
+fn think_stops_when_searching_deeper_cant_help() {
+    // only one legal move
+    let board = Board::from_fen("k7/8/1K6/8/8/8/8/7R b - - 0 1").unwrap();
+    let mut depths = Vec::new();
+    let result = Searcher::new().think(&board, &Limits::default(), |info| depths.push(info.depth));
+    assert_eq!(depths, [1]);
+    assert_eq!(result.best_move.unwrap().to_string(), "a8b8");
+}
+
+#[test]
+fn think_searches_deeper_from_a_repeated_position() {
+    // the position has come up once before which isn't a draw yet
+    let mut board =
+        Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+            .unwrap();
+    for notation in ["f3g1", "c6b8", "g1f3", "b8c6"] {
+        let legal_move = board.as_legal_move(notation.parse().unwrap()).unwrap();
+        board.make_move(legal_move);
+    }
+
+    let mut depths = Vec::new();
+    let limits = Limits {
+        depth: Some(4),
+        ..Limits::default()
+    };
+    let result = Searcher::new().think(&board, &limits, |info| depths.push(info.depth));
+    assert_eq!(depths, [1, 2, 3, 4]);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn think_keeps_to_the_time() {
+    let board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+        .unwrap();
+    let start_time = Instant::now();
+    let limits = Limits {
+        move_time: Some(Duration::from_millis(200)),
+        ..Limits::default()
+    };
+    let result = Searcher::new().think(&board, &limits, |_| ());
+    assert!(start_time.elapsed() < Duration::from_millis(1000));
+    assert!(result.best_move.is_some());
+
+    // only a small part of the clock is used for one move
+    let start_time = Instant::now();
+    let limits = Limits {
+        time_left: [Some(Duration::from_secs(3)), Some(Duration::from_secs(3))],
+        ..Limits::default()
+    };
+    let result = Searcher::new().think(&board, &limits, |_| ());
+    assert!(start_time.elapsed() < Duration::from_millis(1500));
+    assert!(result.best_move.is_some());
+
+    // a stopped search still gives a move
+    let mut searcher = Searcher::new();
+    searcher.stop_flag().store(true, Ordering::Relaxed);
+    let limits = Limits {
+        infinite: true,
+        ..Limits::default()
+    };
+    let result = searcher.think(&board, &limits, |_| ());
+    assert!(result.stopped);
+    assert!(result.best_move.is_some());
+}
@@ -0,0 +1,61 @@
+use cheseng::Board;
+
+fn assert_see(fen: &str, notation: &str, expected: i32) {
+    let board = Board::from_fen(fen).unwrap();
+    let test_move = board.as_legal_move(notation.parse().unwrap()).unwrap();
+    assert_eq!(board.see(test_move), expected, "{} {}", fen, notation);
+    assert!(board.see_ge(test_move, expected), "{} {}", fen, notation);
+    assert!(
+        !board.see_ge(test_move, expected + 1),
+        "{} {}",
+        fen,
+        notation
+    );
+}
+
+#[test]
+fn see_wins_undefended_pieces() {
+    assert_see(
+        "1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1",
+        "e1e5",
+        100,
+    );
+    assert_see("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", "d2d5", 900);
+}
+
+#[test]
+fn see_loses_to_recaptures() {
+    // the pawn is defended by a pawn
+    assert_see("4k3/8/4p3/3p4/8/8/3Q4/4K3 w - - 0 1", "d2d5", -800);
+
+    // the knight takes a pawn and both sides trade down until it is lost
+    assert_see(
+        "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+        "d3e5",
+        -220,
+    );
+
+    // a move to an attacked square loses the piece even without capturing
+    assert_see("4k3/8/8/4p3/8/8/3Q4/4K3 w - - 0 1", "d2d4", -900);
+    assert_see("4k3/8/8/8/8/8/3Q4/4K3 w - - 0 1", "d2d4", 0);
+}
+
+#[test]
+fn see_counts_x_rays() {
+    // the rook behind the first one takes back after the first is taken
+    assert_see("4r1k1/8/8/4p3/8/8/4R3/4R1K1 w - - 0 1", "e2e5", 100);
+    assert_see("4r1k1/8/8/4p3/8/8/4R3/6K1 w - - 0 1", "e2e5", -400);
+
+    // the king can't take back when the second rook defends the first
+    assert_see("8/8/4k3/3p4/8/8/3R4/3R2K1 w - - 0 1", "d2d5", 100);
+    assert_see("8/8/4k3/3p4/8/8/3R4/6K1 w - - 0 1", "d2d5", -400);
+}
+
+#[test]
+fn see_handles_special_moves() {
+    assert_see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6", 100);
+    // promoting gains the queen minus the pawn but the new queen gets taken back
+    assert_see("3rk3/2P5/8/8/8/8/8/4K3 w - - 0 1", "c7c8q", -100);
+    assert_see("3rk3/2P5/8/8/8/8/8/4K3 w - - 0 1", "c7d8q", 400);
+    assert_see("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1g1", 0);
+}
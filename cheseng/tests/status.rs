@@ -0,0 +1,140 @@
+use cheseng::{Board, Color, DrawReason, GameStatus, Move};
+
+fn status(fen: &str) -> GameStatus {
+    Board::from_fen(fen).unwrap().status()
+}
+
+#[test]
+fn ongoing() {
+    assert_eq!(Board::default().status(), GameStatus::Ongoing);
+    // in check but can escape
+    assert_eq!(
+        status("4k3/8/8/8/8/8/8/r3K3 w - - 0 1"),
+        GameStatus::Ongoing
+    );
+}
+
+#[test]
+fn checkmate() {
+    // fool's mate
+    assert_eq!(
+        status("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"),
+        GameStatus::Checkmate(Color::Black)
+    );
+    // back rank mate
+    assert_eq!(
+        status("3R2k1/5ppp/8/8/8/8/8/6K1 b - - 0 1"),
+        GameStatus::Checkmate(Color::White)
+    );
+}
+
+#[test]
+fn stalemate() {
+    assert_eq!(
+        status("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"),
+        GameStatus::Stalemate
+    );
+}
+
+#[test]
+fn insufficient_material() {
+    let draw = GameStatus::Draw(DrawReason::InsufficientMaterial);
+    assert_eq!(status("4k3/8/8/8/8/8/8/4K3 w - - 0 1"), draw);
+    assert_eq!(status("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1"), draw);
+    assert_eq!(status("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1"), draw);
+    // bishops all on dark squares
+    assert_eq!(status("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1"), draw);
+
+    // bishops on opposite colours, two knights, pawns
+    assert_eq!(
+        status("2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1"),
+        GameStatus::Ongoing
+    );
+    assert_eq!(
+        status("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1"),
+        GameStatus::Ongoing
+    );
+    assert_eq!(
+        status("4k3/8/8/8/8/8/P7/4K3 w - - 0 1"),
+        GameStatus::Ongoing
+    );
+}
+
+#[test]
+fn repetition() {
+    let mut board = Board::default();
+    let knight_moves = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    for _ in 0..2 {
+        for knight_move in knight_moves {
+            assert_eq!(board.status(), GameStatus::Ongoing);
+            board.make_move(knight_move.parse::<Move>().unwrap());
+        }
+    }
+
+    // a threefold repetition has to be claimed
+    assert_eq!(board.repetition_count(), 3);
+    assert_eq!(board.status(), GameStatus::Ongoing);
+    assert_eq!(
+        board.claimable_draw(),
+        Some(DrawReason::ThreefoldRepetition)
+    );
+
+    for knight_move in knight_moves.iter().cycle().take(8) {
+        board.make_move(knight_move.parse::<Move>().unwrap());
+    }
+
+    assert_eq!(
+        board.status(),
+        GameStatus::Draw(DrawReason::FivefoldRepetition)
+    );
+    assert_eq!(board.claimable_draw(), None);
+}
+
+#[test]
+fn pawn_move_resets_repetitions() {
+    let mut board = Board::default();
+    for raw_move in ["g1f3", "g8f6", "f3g1", "f6g8", "e2e3", "e7e6"] {
+        board.make_move(raw_move.parse::<Move>().unwrap());
+    }
+
+    assert_eq!(board.halfmove_clock, 0);
+    assert_eq!(board.repetition_count(), 1);
+}
+
+#[test]
+fn fifty_move_rule() {
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    board.halfmove_clock = 99;
+    assert_eq!(board.status(), GameStatus::Ongoing);
+
+    assert_eq!(board.claimable_draw(), None);
+
+    // the fifty move rule has to be claimed
+    board.make_move("a1a2".parse::<Move>().unwrap());
+    assert_eq!(board.status(), GameStatus::Ongoing);
+    assert_eq!(board.claimable_draw(), Some(DrawReason::FiftyMoveRule));
+
+    board.halfmove_clock = 150;
+    assert_eq!(
+        board.status(),
+        GameStatus::Draw(DrawReason::SeventyFiveMoveRule)
+    );
+}
+
+#[test]
+fn only_some_draws_are_claimable() {
+    assert!(DrawReason::ThreefoldRepetition.is_claimable());
+    assert!(DrawReason::FiftyMoveRule.is_claimable());
+    assert!(!DrawReason::FivefoldRepetition.is_claimable());
+    assert!(!DrawReason::SeventyFiveMoveRule.is_claimable());
+    assert!(!DrawReason::InsufficientMaterial.is_claimable());
+}
+
+#[test]
+fn checkmate_beats_fifty_move_rule() {
+    let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    board.halfmove_clock = 99;
+    board.make_move("a1a8".parse::<Move>().unwrap());
+    assert_eq!(board.status(), GameStatus::Checkmate(Color::White));
+    assert_eq!(board.claimable_draw(), None);
+}
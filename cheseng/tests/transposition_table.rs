@@ -0,0 +1,163 @@
+use cheseng::search::{
+    search, Bound, ReplacementPolicy, Searcher, TranspositionTable, DEFAULT_BUCKET_SIZE, MATE_SCORE,
+};
+use cheseng::{Board, Move};
+
+fn test_move(notation: &str) -> Option<Move> {
+    Some(notation.parse().unwrap())
+}
+
+#[test]
+fn table_stores_and_probes() {
+    let mut table = TranspositionTable::new(1);
+    assert_eq!(table.probe(1234, 0), None);
+
+    table.store(1234, 5, 0, 42, Bound::Lower, test_move("e2e4"));
+    let entry = table.probe(1234, 0).unwrap();
+    assert_eq!(entry.key, 1234);
+    assert_eq!(entry.depth, 5);
+    assert_eq!(entry.score, 42);
+    assert_eq!(entry.bound, Bound::Lower);
+    assert_eq!(table.best_move(1234), test_move("e2e4"));
+    assert_eq!(table.probe(4321, 0), None);
+
+    table.clear();
+    assert_eq!(table.probe(1234, 0), None);
+
+    table.store(1234, 5, 0, 42, Bound::Exact, None);
+    table.resize(2);
+    assert_eq!(table.probe(1234, 0), None);
+}
+
+#[test]
+fn table_adjusts_mate_scores() {
+    let mut table = TranspositionTable::new(1);
+
+    // mate in 3 plies found 4 plies from the root is mate in 3 plies from the position
+    table.store(1, 3, 4, MATE_SCORE - 7, Bound::Exact, None);
+    assert_eq!(table.probe(1, 4).unwrap().score, MATE_SCORE - 7);
+    assert_eq!(table.probe(1, 2).unwrap().score, MATE_SCORE - 5);
+
+    table.store(2, 3, 4, -MATE_SCORE + 7, Bound::Exact, None);
+    assert_eq!(table.probe(2, 6).unwrap().score, -MATE_SCORE + 9);
+
+    // other scores stay the same
+    table.store(3, 3, 4, 150, Bound::Exact, None);
+    assert_eq!(table.probe(3, 10).unwrap().score, 150);
+}
+
+#[test]
+fn table_replaces_entries() {
+    let mut table = TranspositionTable::new(1);
+
+    // a shallower bound doesn't replace a deeper entry from the same search
+    table.store(1, 6, 0, 10, Bound::Exact, test_move("e2e4"));
+    table.store(1, 2, 0, 20, Bound::Lower, test_move("d2d4"));
+    assert_eq!(table.probe(1, 0).unwrap().score, 10);
+
+    // but an exact score does and the old best move is kept when there isn't a new one
+    table.store(1, 2, 0, 30, Bound::Exact, None);
+    let entry = table.probe(1, 0).unwrap();
+    assert_eq!(entry.score, 30);
+    assert_eq!(entry.best_move, test_move("e2e4"));
+
+    // anything from a new search replaces it
+    table.new_search();
+    table.store(1, 1, 0, 40, Bound::Upper, test_move("g1f3"));
+    let entry = table.probe(1, 0).unwrap();
+    assert_eq!(entry.score, 40);
+    assert_eq!(entry.best_move, test_move("g1f3"));
+}
+
+#[test]
+fn table_buckets_hold_the_configured_number_of_entries() {
+    assert_eq!(
+        TranspositionTable::new(1).bucket_size(),
+        DEFAULT_BUCKET_SIZE
+    );
+
+    // a table too small for a megabyte has a single bucket
+    let mut table = TranspositionTable::with_options(0, 1, ReplacementPolicy::default());
+    assert_eq!(table.bucket_size(), 1);
+    table.store(1, 5, 0, 10, Bound::Exact, None);
+    table.store(2, 1, 0, 20, Bound::Exact, None);
+    assert_eq!(table.probe(1, 0), None);
+    assert_eq!(table.probe(2, 0).unwrap().score, 20);
+
+    let mut table = TranspositionTable::with_options(0, 4, ReplacementPolicy::default());
+    for key in 1..=4 {
+        table.store(key, 5, 0, key as i32, Bound::Exact, None);
+    }
+    assert!((1..=4).all(|key| table.probe(key, 0).is_some()));
+}
+
+#[test]
+fn table_replaces_entries_by_the_policy() {
+    let store_two_searches = |policy| {
+        let mut table = TranspositionTable::with_options(0, 2, policy);
+        assert_eq!(table.replacement_policy(), policy);
+        table.store(1, 6, 0, 10, Bound::Exact, None);
+        table.new_search();
+        table.new_search();
+        table.store(2, 3, 0, 20, Bound::Exact, None);
+        // the bucket is full so one of them is replaced
+        table.store(3, 1, 0, 30, Bound::Exact, None);
+        // a shallower bound for the same position
+        table.store(3, 0, 0, 40, Bound::Lower, None);
+        table
+    };
+
+    // the deep entry from two searches ago goes first
+    let table = store_two_searches(ReplacementPolicy::DepthAndAge);
+    assert_eq!(table.probe(1, 0), None);
+    assert!(table.probe(2, 0).is_some());
+    assert_eq!(table.probe(3, 0).unwrap().score, 30);
+
+    // the deepest entry stays no matter how old it is
+    let table = store_two_searches(ReplacementPolicy::DepthPreferred);
+    assert!(table.probe(1, 0).is_some());
+    assert_eq!(table.probe(2, 0), None);
+    assert_eq!(table.probe(3, 0).unwrap().score, 30);
+
+    // the oldest entry goes and the same position is always overwritten
+    let table = store_two_searches(ReplacementPolicy::Always);
+    assert_eq!(table.probe(1, 0), None);
+    assert!(table.probe(2, 0).is_some());
+    assert_eq!(table.probe(3, 0).unwrap().score, 40);
+}
+
+#[test]
+fn table_counts_how_full_it_is() {
+    let mut searcher = Searcher::new();
+    searcher.transposition_table().resize(1);
+    assert_eq!(searcher.transposition_table().hashfull(), 0);
+
+    searcher.search(&Board::default(), 5);
+    let table = searcher.transposition_table();
+    assert!(table.hashfull() > 0);
+
+    // entries from older searches aren't counted
+    table.new_search();
+    assert_eq!(table.hashfull(), 0);
+}
+
+#[test]
+fn search_gives_the_same_results_with_the_table_filled() {
+    let fen = "k7/8/2K5/8/8/8/8/7R w - - 0 1";
+    let board = Board::from_fen(fen).unwrap();
+    let expected = search(&board, 4);
+
+    // searching again uses the entries from the first search
+    let mut searcher = Searcher::new();
+    searcher.search(&board, 4);
+    let result = searcher.search(&board, 4);
+    assert_eq!(result.best_move, expected.best_move);
+    assert_eq!(result.score, expected.score);
+    assert!(result.nodes < expected.nodes);
+
+    let mut board = board;
+    for pv_move in result.pv {
+        assert_eq!(board.as_legal_move(pv_move), Some(pv_move));
+        board.make_move(pv_move);
+    }
+}
@@ -0,0 +1,76 @@
+use cheseng::{Bitboard, Board, Color, Move};
+
+/// Checks the bitboards have the same pieces on them as the grid
+fn assert_bitboards_match_grid(board: &Board) {
+    let mut occupancy = Bitboard::EMPTY;
+    for (index, piece) in board.grid().iter().enumerate() {
+        if let Some(piece) = *piece {
+            assert!(board.pieces(piece).contains(index as u8));
+            occupancy |= Bitboard::from_index(index as u8);
+        }
+    }
+
+    assert_eq!(board.occupancy(), occupancy);
+    assert_eq!(
+        board.color_pieces(Color::White) | board.color_pieces(Color::Black),
+        occupancy
+    );
+}
+
+/// Makes and unmakes every move down to the depth checking the board is restored each time
+fn assert_unmake_restores(board: &mut Board, depth: u32) {
+    if depth == 0 {
+        return;
+    }
+
+    let fen = board.to_fen();
+    let repetition_count = board.repetition_count();
+    for legal_move in board.get_all_legal_moves() {
+        board.make_move(legal_move);
+        assert_bitboards_match_grid(board);
+        assert_unmake_restores(board, depth - 1);
+        board.unmake_move();
+
+        assert_eq!(board.to_fen(), fen);
+        assert_eq!(board.repetition_count(), repetition_count);
+        assert_bitboards_match_grid(board);
+    }
+}
+
+#[test]
+fn unmake_restores_board() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        // castling, en passant and promotions
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ] {
+        assert_unmake_restores(&mut Board::from_fen(fen).unwrap(), 3);
+    }
+}
+
+#[test]
+fn unmake_returns_moves_in_reverse() {
+    let mut board = Board::default();
+    assert!(board.unmake_move().is_none());
+
+    let moves = ["e2e4", "e7e5", "g1f3"];
+    for raw_move in moves {
+        let raw_move = raw_move.parse::<Move>().unwrap();
+        board.make_move(board.as_legal_move(raw_move).unwrap());
+    }
+    assert_eq!(board.moves_made().count(), 3);
+
+    for raw_move in moves.iter().rev() {
+        let raw_move = raw_move.parse::<Move>().unwrap();
+        let unmade_move = board.unmake_move().unwrap();
+        assert_eq!(
+            (unmade_move.start_index, unmade_move.end_index),
+            (raw_move.start_index, raw_move.end_index)
+        );
+    }
+
+    assert_eq!(board.to_fen(), Board::default().to_fen());
+    assert!(board.unmake_move().is_none());
+}
@@ -0,0 +1,112 @@
+use cheseng::{Board, Move};
+
+// positions and keys from the Polyglot book format specification
+const POLYGLOT_KEYS: [(&str, u64); 9] = [
+    (
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        0x463b96181691fc9c,
+    ),
+    (
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        0x823c9b50fd114196,
+    ),
+    (
+        "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2",
+        0x0756b94461c50fb0,
+    ),
+    (
+        "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2",
+        0x662fafb965db29d4,
+    ),
+    (
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        0x22a48b5a8e47ff78,
+    ),
+    (
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3",
+        0x652a607ca3f242c1,
+    ),
+    (
+        "rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4",
+        0x00fdd303c946bdd9,
+    ),
+    (
+        "rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3",
+        0x3c8123ea7b067637,
+    ),
+    (
+        "rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4",
+        0x5c3f9b829b279560,
+    ),
+];
+
+#[test]
+fn polyglot_keys_from_fen() {
+    for (fen, key) in POLYGLOT_KEYS {
+        assert_eq!(Board::from_fen(fen).unwrap().zobrist_key(), key, "{}", fen);
+    }
+}
+
+#[test]
+fn polyglot_keys_after_moves() {
+    // moves with the index of the reference position they lead to
+    let move_sequences: [&[(&str, Option<usize>)]; 2] = [
+        &[
+            ("e2e4", Some(1)),
+            ("d7d5", Some(2)),
+            ("e4e5", Some(3)),
+            ("f7f5", Some(4)),
+            ("e1e2", Some(5)),
+            ("e8f7", Some(6)),
+        ],
+        &[
+            ("a2a4", None),
+            ("b7b5", None),
+            ("h2h4", None),
+            ("b5b4", None),
+            ("c2c4", Some(7)),
+            ("b4c3", None),
+            ("a1a3", Some(8)),
+        ],
+    ];
+
+    for move_sequence in move_sequences {
+        let mut board = Board::default();
+        for &(raw_move, key_index) in move_sequence {
+            let raw_move = raw_move.parse::<Move>().unwrap();
+            board.make_move(board.as_legal_move(raw_move).unwrap());
+            if let Some(key_index) = key_index {
+                assert_eq!(board.zobrist_key(), POLYGLOT_KEYS[key_index].1);
+            }
+        }
+
+        // unmaking should restore the starting key
+        while board.unmake_move().is_some() {}
+        assert_eq!(board.zobrist_key(), POLYGLOT_KEYS[0].1);
+    }
+}
+
+/// Checks the incrementally updated key matches one calculated from scratch for every position
+fn assert_incremental_key(board: &mut Board, depth: u32) {
+    let fen_key = Board::from_fen(&board.to_fen()).unwrap().zobrist_key();
+    assert_eq!(board.zobrist_key(), fen_key, "{}", board.to_fen());
+    if depth == 0 {
+        return;
+    }
+
+    for legal_move in board.get_all_legal_moves() {
+        board.make_move(legal_move);
+        assert_incremental_key(board, depth - 1);
+        board.unmake_move();
+    }
+}
+
+#[test]
+fn incremental_keys() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    ] {
+        assert_incremental_key(&mut Board::from_fen(fen).unwrap(), 2);
+    }
+}
@@ -1,33 +1,130 @@
 use std::io::Write;
 
 fn main() {
-    let mut board = cheseng::Board::default();
-    println!("{}\n", board);
+    // optionally start from a FEN passed as the first argument
+    let mut game = match std::env::args().nth(1) {
+        Some(fen) => match cheseng::Game::from_fen(&fen) {
+            Ok(game) => game,
+            Err(err) => {
+                println!("{:?}", err);
+                return;
+            }
+        },
+        None => cheseng::Game::new(),
+    };
+    println!("{}\n", game.board());
 
     loop {
-        if let Err(err) = on_update(&mut board) {
-            println!("{}", err);
+        let input = match get_input(
+            "Enter move (eg. Nf3 or g1f3), fen, perft <depth>, goto <ply>, save <file> or undo: ",
+        ) {
+            Ok(Some(input)) => input,
+            // end of input
+            Ok(None) => break,
+            Err(_) => {
+                println!("Failed to get input!");
+                break;
+            }
+        };
+
+        match on_update(&mut game, &input) {
+            // the session only ends on a move that finishes the game, not on going back to the
+            // end of a finished game
+            Ok(true) => {
+                let status = game.status();
+                if status.is_over() {
+                    println!("{}", status);
+                    break;
+                }
+            }
+            Ok(false) => (),
+            Err(err) => println!("{}", err),
+        }
+    }
+}
+
+/// Runs the input and returns if it made a move
+fn on_update(game: &mut cheseng::Game, input: &str) -> Result<bool, &'static str> {
+    match input {
+        "fen" => {
+            println!("{}", game.board().to_fen());
+            return Ok(false);
         }
+        "undo" => {
+            game.take_back().ok_or("No move to undo!")?;
+            println!("\n{}\n", game.board());
+            return Ok(false);
+        }
+        _ => (),
+    }
+
+    // moves after the ply are kept until a different move is made
+    if let Some(ply) = input.strip_prefix("goto ") {
+        let ply = ply.parse::<usize>().or(Err("Invalid ply!"))?;
+        if !game.go_to_ply(ply) {
+            return Err("The game doesn't have that many moves!");
+        }
+        println!("\n{}\n", game.board());
+        return Ok(false);
+    }
+
+    if let Some(path) = input.strip_prefix("save ") {
+        std::fs::write(path.trim(), game.to_pgn().to_string()).or(Err("Failed to save game!"))?;
+        println!("Saved game to {}", path.trim());
+        return Ok(false);
+    }
+
+    if let Some(depth) = input.strip_prefix("perft ") {
+        let depth = depth.parse::<u32>().or(Err("Invalid perft depth!"))?;
+        print_perft_divide(game.board(), depth);
+        return Ok(false);
     }
+
+    // try SAN (eg. Nf3) first then coordinates (eg. g1f3)
+    let board = game.board();
+    let legal_move = match board.parse_san(input) {
+        Ok(legal_move) => legal_move,
+        Err(cheseng::Error::InvalidSAN(cheseng::SanError::AmbiguousMove)) => {
+            return Err("Ambiguous move! Add the file or rank the piece moves from.")
+        }
+        Err(_) => {
+            let test_move = input
+                .parse::<cheseng::Move>()
+                .or(Err("Invalid move notation!"))?;
+            board.as_legal_move(test_move).ok_or("Not a legal move!")?
+        }
+    };
+
+    game.make_move(legal_move);
+    println!("\n{}\n", game.board());
+    Ok(true)
 }
 
-fn on_update(board: &mut cheseng::Board) -> Result<(), &'static str> {
-    let input = get_input("Enter move (eg. e2e4): ").or(Err("Failed to get input!"))?;
-    let test_move = input
-        .parse::<cheseng::Move>()
-        .or(Err("Invalid move notation!"))?;
+fn print_perft_divide(board: &cheseng::Board, depth: u32) {
+    let start_time = std::time::Instant::now();
+    let divide = cheseng::perft::perft_divide(board, depth);
+    let elapsed = start_time.elapsed();
 
-    let legal_move = board.as_legal_move(test_move).ok_or("Not a legal move!")?;
-    board.make_move(legal_move);
-    println!("\n{}\n", board);
-    Ok(())
+    for (divide_move, num_nodes) in &divide {
+        println!("{}: {}", divide_move, num_nodes);
+    }
+
+    let total: u64 = divide.iter().map(|(_, num_nodes)| num_nodes).sum();
+    println!(
+        "\nNodes searched: {} in {:.3}s\n",
+        total,
+        elapsed.as_secs_f64()
+    );
 }
 
-fn get_input(message: &str) -> std::io::Result<String> {
+/// Gets a line of input or none if there is no more input
+fn get_input(message: &str) -> std::io::Result<Option<String>> {
     print!("{}", message);
     std::io::stdout().flush()?;
 
     let mut buffer = String::new();
-    std::io::stdin().read_line(&mut buffer)?;
-    Ok(buffer.trim_end().to_owned())
+    if std::io::stdin().read_line(&mut buffer)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(buffer.trim_end().to_owned()))
 }
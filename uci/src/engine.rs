@@ -0,0 +1,125 @@
+use cheseng::search::{Limits, SearchInfo, Searcher, TranspositionTable};
+use cheseng::{Game, Move};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// The game being played along with the search running on it. Front ends for each protocol
+/// drive the engine and write its output in their own format.
+pub struct Engine {
+    pub game: Game,
+    /// The searcher when it isn't being used by the search thread
+    searcher: Option<Searcher>,
+    stop: Arc<AtomicBool>,
+    /// Set when the search is aborted so its best move isn't given
+    discard: Arc<AtomicBool>,
+    search_thread: Option<JoinHandle<Searcher>>,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        let searcher = Searcher::new();
+        Self {
+            game: Game::new(),
+            stop: searcher.stop_flag(),
+            discard: Arc::new(AtomicBool::new(false)),
+            searcher: Some(searcher),
+            search_thread: None,
+        }
+    }
+
+    /// Starts searching the current position on another thread. The info callback is called
+    /// as the search makes progress and the best move callback once it's done (with none if
+    /// there are no legal moves) unless the search is aborted.
+    pub fn start_search<I, B>(&mut self, limits: Limits, on_info: I, on_best_move: B)
+    where
+        I: Fn(&SearchInfo) + Send + 'static,
+        B: FnOnce(Option<Move>) + Send + 'static,
+    {
+        self.stop_search();
+        self.stop.store(false, Ordering::Relaxed);
+        self.discard.store(false, Ordering::Relaxed);
+
+        let board = self.game.board().clone();
+        let stop = Arc::clone(&self.stop);
+        let discard = Arc::clone(&self.discard);
+        let mut searcher = self.searcher.take().expect("Searcher is being used!");
+        self.search_thread = Some(std::thread::spawn(move || {
+            let best_move = searcher.think(&board, &limits, on_info).best_move;
+
+            // an infinite search only gives its move once it's stopped
+            while limits.infinite && !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+
+            if !discard.load(Ordering::Relaxed) {
+                on_best_move(best_move);
+            }
+            searcher
+        }));
+    }
+
+    /// Stops the search and waits for it to give its best move
+    pub fn stop_search(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(search_thread) = self.search_thread.take() {
+            self.searcher = Some(search_thread.join().expect("Search thread panicked!"));
+        }
+    }
+
+    /// Stops the search without giving its best move
+    pub fn abort_search(&mut self) {
+        self.discard.store(true, Ordering::Relaxed);
+        self.stop_search();
+    }
+
+    /// Gets the searcher's transposition table to resize or clear once the search is stopped
+    pub fn transposition_table(&mut self) -> &mut TranspositionTable {
+        self.searcher().transposition_table()
+    }
+
+    /// Goes back to the starting position and forgets what was learnt in the last game
+    pub fn new_game(&mut self) {
+        self.searcher().new_game();
+        self.game = Game::new();
+    }
+
+    /// Gets the searcher once the search is stopped
+    fn searcher(&mut self) -> &mut Searcher {
+        self.stop_search();
+        self.searcher.as_mut().expect("Searcher is being used!")
+    }
+
+    /// Sets up the position from a FEN (or the starting position without one) and makes the
+    /// moves given in coordinate notation (eg. e2e4, e7e8q). Returns the first move that isn't
+    /// legal after making the moves before it.
+    pub fn set_position<'a>(
+        &mut self,
+        fen: Option<&str>,
+        moves: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), String> {
+        self.stop_search();
+        self.game = match fen {
+            Some(fen) => Game::from_fen(fen).map_err(|err| format!("{:?}", err))?,
+            None => Game::new(),
+        };
+
+        for notation in moves {
+            let legal_move = notation
+                .parse::<Move>()
+                .ok()
+                .and_then(|test_move| self.game.board().as_legal_move(test_move))
+                .ok_or_else(|| notation.to_owned())?;
+            self.game.make_move(legal_move);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        self.abort_search();
+    }
+}
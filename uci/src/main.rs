@@ -0,0 +1,17 @@
+mod engine;
+mod uci;
+mod xboard;
+
+fn main() {
+    let mut commands = std::io::stdin()
+        .lines()
+        .map_while(Result::ok)
+        .skip_while(|command| command.trim().is_empty());
+
+    // GUIs that speak the Chess Engine Communication Protocol say so first, otherwise it's UCI
+    match commands.next() {
+        Some(command) if command.trim() == "xboard" => xboard::run(commands),
+        Some(command) => uci::run(std::iter::once(command).chain(commands)),
+        None => (),
+    }
+}
@@ -0,0 +1,144 @@
+use crate::engine::Engine;
+use cheseng::search::{Limits, Score, SearchInfo, DEFAULT_HASH_SIZE_MB};
+use std::time::Duration;
+
+/// Largest hash table size in megabytes that can be set
+const MAX_HASH_SIZE_MB: usize = 4096;
+
+/// Talks to the GUI with the Universal Chess Interface until it quits or there is no more input
+pub fn run(commands: impl Iterator<Item = String>) {
+    let mut engine = Engine::new();
+    for command in commands {
+        if !on_command(&mut engine, command.trim()) {
+            break;
+        }
+    }
+}
+
+/// Runs a command from the GUI. Returns false when the engine should quit.
+fn on_command(engine: &mut Engine, command: &str) -> bool {
+    let mut tokens = command.split_whitespace();
+    match tokens.next() {
+        Some("uci") => {
+            println!("id name cheseng {}", env!("CARGO_PKG_VERSION"));
+            println!("id author {}", env!("CARGO_PKG_AUTHORS"));
+            println!(
+                "option name Hash type spin default {} min 1 max {}",
+                DEFAULT_HASH_SIZE_MB, MAX_HASH_SIZE_MB
+            );
+            println!("option name Clear Hash type button");
+            println!("uciok");
+        }
+        Some("isready") => println!("readyok"),
+        Some("ucinewgame") => engine.new_game(),
+        Some("position") => on_position(engine, tokens),
+        Some("go") => on_go(engine, tokens),
+        Some("stop") => engine.stop_search(),
+        Some("setoption") => on_set_option(engine, tokens),
+        Some("quit") => return false,
+        // debug, register and ponderhit don't change anything
+        _ => (),
+    }
+
+    true
+}
+
+/// Sets up the position from "position [startpos | fen <fen>] [moves <move>...]"
+fn on_position<'a>(engine: &mut Engine, mut tokens: impl Iterator<Item = &'a str>) {
+    let fen = match tokens.next() {
+        Some("startpos") => None,
+        Some("fen") => Some(
+            tokens
+                .by_ref()
+                .take_while(|&token| token != "moves")
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => {
+            println!("info string expected startpos or fen");
+            return;
+        }
+    };
+
+    // the moves token was already taken after a FEN
+    let moves = tokens.skip_while(|&token| token == "moves");
+    if let Err(err) = engine.set_position(fen.as_deref(), moves) {
+        println!("info string illegal move or position {}", err);
+    }
+}
+
+/// Starts searching with the limits from "go [depth <d>] [nodes <n>] [movetime <ms>]
+/// [wtime <ms>] [btime <ms>] [winc <ms>] [binc <ms>] [movestogo <n>] [infinite]"
+fn on_go<'a>(engine: &mut Engine, mut tokens: impl Iterator<Item = &'a str>) {
+    let mut limits = Limits::default();
+    while let Some(token) = tokens.next() {
+        let mut value = || tokens.next().and_then(|value| value.parse::<i64>().ok());
+
+        // some GUIs send negative times when a clock runs out
+        let mut millis = || value().map(|value| Duration::from_millis(value.max(0) as u64));
+
+        match token {
+            "depth" => limits.depth = value().map(|value| value.max(1) as u32),
+            "nodes" => limits.nodes = value().map(|value| value.max(1) as u64),
+            "movetime" => limits.move_time = millis(),
+            "wtime" => limits.time_left[0] = millis(),
+            "btime" => limits.time_left[1] = millis(),
+            "winc" => limits.increment[0] = millis().unwrap_or_default(),
+            "binc" => limits.increment[1] = millis().unwrap_or_default(),
+            "movestogo" => limits.moves_to_go = value().map(|value| value.max(1) as u32),
+            "infinite" => limits.infinite = true,
+            _ => (),
+        }
+    }
+
+    engine.start_search(limits, print_info, |best_move| match best_move {
+        Some(best_move) => println!("bestmove {}", best_move),
+        // there is no legal move to give
+        None => println!("bestmove 0000"),
+    });
+}
+
+/// Handles "setoption name <id> [value <x>]" for the Hash size in megabytes and the Clear Hash
+/// button
+fn on_set_option<'a>(engine: &mut Engine, mut tokens: impl Iterator<Item = &'a str>) {
+    let name: Vec<&str> = tokens
+        .by_ref()
+        .skip_while(|&token| token == "name")
+        .take_while(|&token| token != "value")
+        .collect();
+    let name = name.join(" ");
+    let value: Vec<&str> = tokens.collect();
+    let value = value.join(" ");
+
+    // option names aren't case sensitive
+    match name.to_lowercase().as_str() {
+        "hash" => match value.parse::<usize>() {
+            Ok(size_mb) => engine
+                .transposition_table()
+                .resize(size_mb.clamp(1, MAX_HASH_SIZE_MB)),
+            Err(_) => println!("info string invalid hash size {}", value),
+        },
+        "clear hash" => engine.transposition_table().clear(),
+        _ => println!("info string unknown option {}", name),
+    }
+}
+
+fn print_info(info: &SearchInfo) {
+    let score = match info.score {
+        Score::Centipawns(centipawns) => format!("cp {}", centipawns),
+        Score::Mate(moves) => format!("mate {}", moves),
+    };
+    let pv: Vec<String> = info.pv.iter().map(|pv_move| pv_move.to_string()).collect();
+    let time = info.time.as_millis().max(1);
+
+    println!(
+        "info depth {} score {} nodes {} nps {} time {} hashfull {} pv {}",
+        info.depth,
+        score,
+        info.nodes,
+        info.nodes as u128 * 1000 / time,
+        info.time.as_millis(),
+        info.hashfull,
+        pv.join(" ")
+    );
+}
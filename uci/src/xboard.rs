@@ -0,0 +1,303 @@
+use crate::engine::Engine;
+use cheseng::pgn::GameResult;
+use cheseng::search::{Limits, Score, SearchInfo};
+use cheseng::{Board, Color, GameStatus, Move};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+/// Talks to the GUI with the Chess Engine Communication Protocol (xboard/WinBoard) until it
+/// quits or there is no more input
+pub fn run(commands: impl Iterator<Item = String>) {
+    let mut xboard = XBoard::new();
+    for command in commands {
+        if !xboard.on_command(command.trim()) {
+            break;
+        }
+    }
+}
+
+struct XBoard {
+    engine: Engine,
+    /// The colour the engine plays or none in force mode
+    engine_color: Option<Color>,
+    /// Moves per time control (0 for the whole game) from the level command
+    moves_per_session: u32,
+    increment: Duration,
+    /// Exact time per move from the st command
+    move_time: Option<Duration>,
+    /// Depth from the sd command
+    depth: Option<u32>,
+    /// Time left on the clocks from the time and otim commands which start at the base time of
+    /// the level command
+    engine_time: Option<Duration>,
+    opponent_time: Option<Duration>,
+    /// Show the thinking output
+    post: bool,
+    /// Moves found by the search which are made once the search is done
+    engine_moves: (Sender<Move>, Receiver<Move>),
+}
+
+impl XBoard {
+    fn new() -> Self {
+        Self {
+            engine: Engine::new(),
+            engine_color: Some(Color::Black),
+            moves_per_session: 0,
+            increment: Duration::ZERO,
+            move_time: None,
+            depth: None,
+            engine_time: None,
+            opponent_time: None,
+            post: false,
+            engine_moves: mpsc::channel(),
+        }
+    }
+
+    /// Runs a command from the GUI. Returns false when the engine should quit.
+    fn on_command(&mut self, command: &str) -> bool {
+        let (name, args) = command.split_once(' ').unwrap_or((command, ""));
+        let args = args.trim();
+
+        // make the move found by a search that finished on its own
+        self.make_engine_moves();
+
+        match name {
+            "protover" => {
+                println!(
+                    "feature myname=\"cheseng {}\" ping=1 setboard=1 usermove=1 time=1 draw=0 \
+                     sigint=0 sigterm=0 reuse=1 analyze=0 colors=0 san=0 memory=1 done=1",
+                    env!("CARGO_PKG_VERSION")
+                );
+            }
+            "new" => {
+                self.abort_search();
+                self.engine.new_game();
+                self.engine_color = Some(Color::Black);
+                self.depth = None;
+            }
+            "setboard" => {
+                self.abort_search();
+                self.set_position(Some(args));
+            }
+            "force" | "result" => {
+                self.abort_search();
+                self.engine_color = None;
+            }
+            "go" => {
+                self.abort_search();
+                self.engine_color = Some(self.engine.game.board().turn);
+                self.start_search();
+            }
+            "usermove" => self.on_user_move(args),
+            // move now which is the only time a stopped search gives its move
+            "?" => self.stop_search(),
+            "undo" => {
+                self.abort_search();
+                self.engine.game.take_back();
+            }
+            "remove" => {
+                self.abort_search();
+                self.engine.game.take_back();
+                self.engine.game.take_back();
+            }
+            "level" => self.on_level(args),
+            "st" => {
+                self.move_time = args
+                    .parse::<f64>()
+                    .ok()
+                    .map(|seconds| Duration::from_secs_f64(seconds.max(0.0)));
+            }
+            "sd" => self.depth = args.parse::<u32>().ok().map(|depth| depth.max(1)),
+            "time" => self.engine_time = parse_centiseconds(args),
+            "otim" => self.opponent_time = parse_centiseconds(args),
+            // hash table size in megabytes
+            "memory" => match args.parse::<usize>() {
+                Ok(size_mb) => self.engine.transposition_table().resize(size_mb.max(1)),
+                Err(_) => println!("Error (bad memory size): {}", command),
+            },
+            "post" => self.post = true,
+            "nopost" => self.post = false,
+            "ping" => println!("pong {}", args),
+            "quit" => return false,
+            // replies to features and commands that don't change anything
+            "accepted" | "rejected" | "random" | "hard" | "easy" | "computer" | "name" | "" => (),
+            _ => println!("Error (unknown command): {}", command),
+        }
+
+        true
+    }
+
+    fn on_user_move(&mut self, notation: &str) {
+        self.abort_search();
+        let legal_move = notation
+            .parse::<Move>()
+            .ok()
+            .and_then(|test_move| self.engine.game.board().as_legal_move(test_move));
+
+        let Some(legal_move) = legal_move else {
+            println!("Illegal move: {}", notation);
+            return;
+        };
+
+        self.engine.game.make_move(legal_move);
+        if print_result(self.engine.game.board()) {
+            return;
+        }
+
+        if self.engine_color == Some(self.engine.game.board().turn) {
+            self.start_search();
+        }
+    }
+
+    /// Sets the time control from "level <moves per session> <minutes[:seconds]> <increment>"
+    fn on_level(&mut self, args: &str) {
+        let mut args = args.split_whitespace();
+        self.moves_per_session = args
+            .next()
+            .and_then(|moves| moves.parse().ok())
+            .unwrap_or(0);
+
+        // both clocks start at the base time until the time and otim commands give the time left
+        let base_time = args.next().and_then(parse_base_time);
+        self.engine_time = base_time;
+        self.opponent_time = base_time;
+
+        self.increment = args
+            .next()
+            .and_then(|seconds| seconds.parse::<f64>().ok())
+            .map(|seconds| Duration::from_secs_f64(seconds.max(0.0)))
+            .unwrap_or_default();
+    }
+
+    fn set_position(&mut self, fen: Option<&str>) {
+        if self.engine.set_position(fen, []).is_err() {
+            println!("tellusererror Illegal position");
+        }
+    }
+
+    fn start_search(&mut self) {
+        let board = self.engine.game.board().clone();
+        let Some(engine_color) = self.engine_color else {
+            return;
+        };
+        if board.status().is_over() {
+            return;
+        }
+
+        let mut limits = Limits {
+            depth: self.depth,
+            move_time: self.move_time,
+            ..Limits::default()
+        };
+        limits.time_left[engine_color.as_index()] = self.engine_time;
+        limits.time_left[engine_color.opposite().as_index()] = self.opponent_time;
+        limits.increment = [self.increment; 2];
+        if self.moves_per_session > 0 {
+            // moves are counted from the start of the game for each session
+            let moves_made = self.engine.game.num_plies() as u32 / 2;
+            limits.moves_to_go = Some(self.moves_per_session - moves_made % self.moves_per_session);
+        }
+
+        let post = self.post;
+        let info_board = board.clone();
+        let on_info = move |info: &SearchInfo| {
+            if post {
+                print_thinking(&info_board, info);
+            }
+        };
+
+        let engine_moves = self.engine_moves.0.clone();
+        let on_best_move = move |best_move: Option<Move>| {
+            let Some(best_move) = best_move else {
+                return;
+            };
+
+            // the move has to be there before the GUI can reply to it
+            engine_moves
+                .send(best_move)
+                .expect("Engine moves receiver dropped!");
+            println!("move {}", best_move);
+
+            let mut board = board;
+            board.make_move(best_move);
+            print_result(&board);
+        };
+
+        self.engine.start_search(limits, on_info, on_best_move);
+    }
+
+    /// Stops the search making the move it found
+    fn stop_search(&mut self) {
+        self.engine.stop_search();
+        self.make_engine_moves();
+    }
+
+    /// Stops the search without making a move. A move the search already gave is still made
+    /// since the GUI has seen it.
+    fn abort_search(&mut self) {
+        self.engine.abort_search();
+        self.make_engine_moves();
+    }
+
+    fn make_engine_moves(&mut self) {
+        while let Ok(engine_move) = self.engine_moves.1.try_recv() {
+            self.engine.game.make_move(engine_move);
+        }
+    }
+}
+
+/// Parses the base time of the level command as "<minutes>" or "<minutes>:<seconds>"
+fn parse_base_time(base_time: &str) -> Option<Duration> {
+    let (minutes, seconds) = base_time.split_once(':').unwrap_or((base_time, "0"));
+    let seconds = minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+fn parse_centiseconds(centiseconds: &str) -> Option<Duration> {
+    let centiseconds = centiseconds.parse::<i64>().ok()?;
+    Some(Duration::from_millis(centiseconds.max(0) as u64 * 10))
+}
+
+/// Prints the thinking output as "<depth> <score> <time in centiseconds> <nodes> <pv>" with the
+/// principal variation in SAN
+fn print_thinking(board: &Board, info: &SearchInfo) {
+    let score = match info.score {
+        Score::Centipawns(centipawns) => centipawns,
+        Score::Mate(moves) if moves > 0 => 100000 + moves,
+        Score::Mate(moves) => -100000 + moves,
+    };
+
+    let mut board = board.clone();
+    let pv: Vec<String> = info
+        .pv
+        .iter()
+        .map(|&pv_move| {
+            let san = board.move_to_san(pv_move);
+            board.make_move(pv_move);
+            san
+        })
+        .collect();
+
+    println!(
+        "{} {} {} {} {}",
+        info.depth,
+        score,
+        info.time.as_millis() / 10,
+        info.nodes,
+        pv.join(" ")
+    );
+}
+
+/// Prints the result if the game is over and returns if it is
+fn print_result(board: &Board) -> bool {
+    let status = board.status();
+    let result = match status {
+        GameStatus::Ongoing => return false,
+        GameStatus::Checkmate(Color::White) => GameResult::WhiteWins,
+        GameStatus::Checkmate(Color::Black) => GameResult::BlackWins,
+        GameStatus::Stalemate | GameStatus::Draw(_) => GameResult::Draw,
+    };
+
+    println!("{} {{{}}}", result, status);
+    true
+}
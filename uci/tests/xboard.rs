@@ -0,0 +1,138 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+/// How long to wait for a line before deciding the engine is stuck
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The engine binary talking xboard with the lines it printed so far
+struct Engine {
+    process: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    output: Vec<String>,
+}
+
+impl Engine {
+    fn start() -> Self {
+        let mut process = Command::new(env!("CARGO_BIN_EXE_cheseng-uci"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = process.stdin.take().unwrap();
+        let stdout = BufReader::new(process.stdout.take().unwrap());
+
+        // read on another thread so waiting for a line can time out
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in stdout.lines() {
+                if sender.send(line.unwrap()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            process,
+            stdin,
+            lines,
+            output: Vec::new(),
+        };
+        engine.send("xboard");
+        engine.send("protover 2");
+        engine.wait_for("feature");
+        engine
+    }
+
+    fn send(&mut self, command: &str) {
+        writeln!(self.stdin, "{}", command).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    /// Waits for a line starting with the prefix and returns it
+    fn wait_for(&mut self, prefix: &str) -> String {
+        loop {
+            let line = self
+                .lines
+                .recv_timeout(TIMEOUT)
+                .unwrap_or_else(|_| panic!("no {:?} line in {:?}", prefix, self.output));
+            self.output.push(line.clone());
+            if line.starts_with(prefix) {
+                return line;
+            }
+        }
+    }
+
+    /// Quits and gets every line the engine printed
+    fn quit(mut self) -> Vec<String> {
+        self.send("quit");
+        self.process.wait().unwrap();
+        self.output.extend(self.lines.iter());
+        self.output
+    }
+}
+
+/// Starts a search that only ends on its own after a long time and waits for its first depth
+fn start_long_search() -> Engine {
+    let mut engine = Engine::start();
+    engine.send("new");
+    engine.send("post");
+    engine.send("st 60");
+    engine.send("usermove e2e4");
+    engine.wait_for("1 ");
+    engine
+}
+
+fn engine_moves(lines: &[String]) -> Vec<&String> {
+    lines
+        .iter()
+        .filter(|line| line.starts_with("move "))
+        .collect()
+}
+
+#[test]
+fn force_during_a_search_gives_no_move() {
+    let mut engine = start_long_search();
+    engine.send("force");
+    engine.send("ping 3");
+    engine.wait_for("pong 3");
+    let lines = engine.quit();
+    assert!(engine_moves(&lines).is_empty(), "{:?}", lines);
+}
+
+#[test]
+fn new_during_a_search_gives_no_move() {
+    let mut engine = start_long_search();
+    engine.send("new");
+    engine.send("ping 3");
+    engine.wait_for("pong 3");
+    let lines = engine.quit();
+    assert!(engine_moves(&lines).is_empty(), "{:?}", lines);
+}
+
+#[test]
+fn move_now_during_a_search_gives_the_move() {
+    let mut engine = start_long_search();
+    engine.send("?");
+    engine.send("ping 3");
+    engine.wait_for("pong 3");
+    let lines = engine.quit();
+    assert_eq!(engine_moves(&lines).len(), 1, "{:?}", lines);
+
+    // the move comes before the reply to the ping
+    let move_index = lines.iter().position(|line| line.starts_with("move "));
+    let pong_index = lines.iter().position(|line| line == "pong 3");
+    assert!(move_index < pong_index);
+}
+
+#[test]
+fn level_base_time_limits_the_search_without_time_commands() {
+    let mut engine = Engine::start();
+    engine.send("new");
+    engine.send("level 0 0:02 0");
+    engine.send("usermove e2e4");
+    engine.wait_for("move ");
+    engine.quit();
+}
@@ -12,7 +12,8 @@ struct PieceWrapper {
 }
 
 pub struct BoardUI {
-    board: cheseng::Board,
+    game: cheseng::Game,
+    status: cheseng::GameStatus,
     pieces_tileset: Texture2D,
     capture_sound: Sound,
     move_sound: Sound,
@@ -30,7 +31,8 @@ macro_rules! load_sound {
 impl BoardUI {
     pub async fn new() -> Self {
         Self {
-            board: cheseng::Board::default(),
+            game: cheseng::Game::new(),
+            status: cheseng::GameStatus::Ongoing,
             pieces_tileset: Texture2D::from_file_with_format(
                 include_bytes!("ChessPieces.png"),
                 None,
@@ -42,16 +44,16 @@ impl BoardUI {
     }
 
     pub fn begin_drag(&mut self, board_pos: cheseng::Position) {
-        if !self.dragged_piece.is_none() {
+        if self.dragged_piece.is_some() || self.status.is_over() {
             return;
         }
 
         if let Ok(index) = board_pos.as_index() {
-            if let Some(internal_piece) = self.board.grid[index as usize] {
+            if let Some(internal_piece) = self.game.board().piece_at(index) {
                 self.dragged_piece = Some(PieceWrapper {
                     internal_piece,
                     index,
-                    legal_moves: internal_piece.get_legal_moves(index, &self.board),
+                    legal_moves: internal_piece.get_legal_moves(index, self.game.board()),
                 });
             }
         }
@@ -70,7 +72,7 @@ impl BoardUI {
                 if let Some(&legal_move) = legal_move {
                     // play capture sound if capture else normal move sound
                     play_sound(
-                        if self.board.move_is_capture(legal_move) {
+                        if self.game.board().move_is_capture(legal_move) {
                             self.capture_sound
                         } else {
                             self.move_sound
@@ -78,7 +80,8 @@ impl BoardUI {
                         PlaySoundParams::default(),
                     );
 
-                    self.board.make_move(legal_move);
+                    self.game.make_move(legal_move);
+                    self.status = self.game.status();
                 }
             }
         }
@@ -87,9 +90,43 @@ impl BoardUI {
         self.dragged_piece = None;
     }
 
+    /// Undoes the move that led to the position being shown
+    pub fn take_back(&mut self) {
+        if self.game.take_back().is_some() {
+            self.status = self.game.status();
+            self.dragged_piece = None;
+        }
+    }
+
+    /// Steps backwards or forwards through the moves of the game without removing any
+    pub fn step_ply(&mut self, forwards: bool) {
+        let ply = if forwards {
+            self.game.ply() + 1
+        } else {
+            self.game.ply().saturating_sub(1)
+        };
+
+        if self.game.go_to_ply(ply) {
+            self.status = self.game.status();
+            self.dragged_piece = None;
+        }
+    }
+
+    /// Saves the game played so far as PGN
+    pub fn save_game(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.game.to_pgn().to_string())
+    }
+
     pub fn draw(&self, screen_view: &SquareViewport) {
         let cell_size = screen_view.cell_size;
-        for (i, piece) in self.board.grid.iter().enumerate() {
+        let board = self.game.board();
+        let checked_king_index = if board.in_check() {
+            board.find_king(board.turn)
+        } else {
+            None
+        };
+
+        for (i, piece) in board.grid().iter().enumerate() {
             let board_pos = cheseng::Position::from_index(i as u8);
             let screen_pos = screen_view.board_to_screen_pos(board_pos);
 
@@ -101,19 +138,31 @@ impl BoardUI {
                 screen_pos.y,
                 cell_size,
                 cell_size,
-                if (board_pos.file + board_pos.rank) % 2 == 0 {
+                if (board_pos.file + board_pos.rank).is_multiple_of(2) {
                     BOARD_COLOR_LIGHT
                 } else {
                     BOARD_COLOR_DARK
                 },
             );
 
+            // highlight king in check
+            if checked_king_index == Some(i as u8) {
+                const CHECK_COLOR: Color = color_u8!(214, 48, 49, 180);
+                draw_rectangle(
+                    screen_pos.x,
+                    screen_pos.y,
+                    cell_size,
+                    cell_size,
+                    CHECK_COLOR,
+                );
+            }
+
             if let Some(piece) = piece {
                 // don't draw the actual dragged_piece that's still on the board
                 if self
                     .dragged_piece
                     .as_ref()
-                    .map_or(true, |piece| piece.index != i as u8)
+                    .is_none_or(|piece| piece.index != i as u8)
                 {
                     self.draw_piece(piece, screen_pos, cell_size);
                 }
@@ -122,16 +171,44 @@ impl BoardUI {
 
         if let Some(piece) = &self.dragged_piece {
             // draw legal moves
-            self.draw_moves_hints(&screen_view, &piece.legal_moves);
+            self.draw_moves_hints(screen_view, &piece.legal_moves);
 
             // draw actual piece at mouse position
             let offset = screen_view.cell_size / 2.0;
             let piece_screen_pos = Vec2::from(mouse_position()) - vec2(offset, offset);
             self.draw_piece(&piece.internal_piece, piece_screen_pos, cell_size);
         }
+
+        if self.status.is_over() {
+            self.draw_status(screen_view);
+        }
+    }
+
+    fn draw_status(&self, screen_view: &SquareViewport) {
+        let board_size = screen_view.cell_size * 8.0;
+        const OVERLAY_COLOR: Color = color_u8!(0, 0, 0, 150);
+        draw_rectangle(
+            screen_view.offset_vec.x,
+            screen_view.offset_vec.y,
+            board_size,
+            board_size,
+            OVERLAY_COLOR,
+        );
+
+        // center text on board
+        let text = self.status.to_string();
+        let font_size = screen_view.cell_size / 2.0;
+        let text_size = measure_text(&text, None, font_size as u16, 1.0);
+        draw_text(
+            &text,
+            screen_view.offset_vec.x + (board_size - text_size.width) / 2.0,
+            screen_view.offset_vec.y + (board_size + text_size.height) / 2.0,
+            font_size,
+            WHITE,
+        );
     }
 
-    fn draw_moves_hints(&self, screen_view: &SquareViewport, moves: &Vec<cheseng::Move>) {
+    fn draw_moves_hints(&self, screen_view: &SquareViewport, moves: &[cheseng::Move]) {
         let cell_size = screen_view.cell_size;
         for &move_draw in moves {
             let board_pos: cheseng::Position = move_draw.end_index.into();
@@ -151,7 +228,7 @@ impl BoardUI {
             }
 
             const MOVE_HINT_COLOR: Color = color_u8!(89, 133, 41, 255);
-            if self.board.move_is_capture(move_draw) {
+            if self.game.board().move_is_capture(move_draw) {
                 // draw captures
                 draw_rectangle_lines(
                     screen_pos.x,
@@ -18,6 +18,23 @@ async fn main() {
             board_ui.end_drag(board_pos);
         }
 
+        if is_key_pressed(KeyCode::Backspace) {
+            board_ui.take_back();
+        }
+
+        if is_key_pressed(KeyCode::Left) {
+            board_ui.step_ply(false);
+        } else if is_key_pressed(KeyCode::Right) {
+            board_ui.step_ply(true);
+        }
+
+        if is_key_pressed(KeyCode::S) {
+            match board_ui.save_game("game.pgn") {
+                Ok(()) => println!("Saved game to game.pgn"),
+                Err(err) => println!("Failed to save game: {}", err),
+            }
+        }
+
         board_ui.draw(&screen_view);
         next_frame().await;
     }
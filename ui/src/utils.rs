@@ -3,7 +3,6 @@ use macroquad::prelude::*;
 pub struct SquareViewport {
     pub offset_vec: Vec2,
     pub cell_size: f32,
-    pub size: f32,
 }
 
 impl SquareViewport {
@@ -11,7 +10,6 @@ impl SquareViewport {
         Self {
             offset_vec: vec2(x, y),
             cell_size: size / 8.0,
-            size,
         }
     }
 